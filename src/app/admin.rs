@@ -0,0 +1,175 @@
+use axum::{
+    Json,
+    async_trait,
+    extract::{FromRequestParts, Path, State},
+    extract::rejection::JsonRejection,
+    http::{StatusCode, request::Parts},
+};
+use schemars::JsonSchema;
+use serde::Deserialize;
+use tokio::sync::oneshot;
+
+use crate::app::{ApiError, AppState, api::CurrentAccount};
+use crate::domain::{BlindLevel, GameCommand, TableDetails};
+use crate::shared::{MAIN_TABLE_ID, MAX_ANNOUNCEMENT_LEN};
+
+/// The username of an operator, from a session token whose account id is listed in
+/// `ADMIN_ACCOUNTS`. Ids rather than usernames, so nobody can become an operator by registering
+/// a listed name first.
+pub struct Admin(pub String);
+
+#[async_trait]
+impl FromRequestParts<AppState> for Admin {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let CurrentAccount(account_id) = CurrentAccount::from_request_parts(parts, state).await?;
+        if !state.admins.contains(&account_id) {
+            return Err(ApiError::forbidden("not an operator"));
+        }
+        let account = state
+            .accounts
+            .account(&account_id)
+            .await
+            .map_err(ApiError::internal)?
+            .ok_or_else(|| ApiError::unauthorized("account no longer exists"))?;
+        Ok(Admin(account.username))
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct StackAdjustment {
+    /// Chips to add; negative to take chips away.
+    amount: i64,
+    reason: String,
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct Announcement {
+    message: String,
+    /// Only this table; every cash table when left out.
+    table_id: Option<String>,
+}
+
+/// Sends a command to a table and hands back the table as it stands afterwards.
+async fn command<T>(
+    state: &AppState,
+    table_id: &str,
+    command: impl FnOnce(oneshot::Sender<Result<T, String>>) -> GameCommand,
+) -> Result<Json<TableDetails>, ApiError> {
+    let table = state.tables.get(table_id).ok_or_else(|| ApiError::not_found("no such table"))?;
+    let (tx, rx) = oneshot::channel();
+    table.commands.send(command(tx)).await.map_err(|_| ApiError::unavailable("table is closed"))?;
+    rx.await.map_err(|_| ApiError::unavailable("table is closed"))?.map_err(ApiError::conflict)?;
+
+    state.tables.details(table_id).await.map(Json).ok_or_else(|| ApiError::unavailable("table is closed"))
+}
+
+pub async fn pause(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    Path(table_id): Path<String>,
+) -> Result<Json<TableDetails>, ApiError> {
+    tracing::info!(%admin, %table_id, "pausing table");
+    command(&state, &table_id, |reply| GameCommand::Pause { reply }).await
+}
+
+pub async fn resume(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    Path(table_id): Path<String>,
+) -> Result<Json<TableDetails>, ApiError> {
+    tracing::info!(%admin, %table_id, "resuming table");
+    command(&state, &table_id, |reply| GameCommand::Resume { reply }).await
+}
+
+pub async fn kick(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    Path((table_id, player_id)): Path<(String, String)>,
+) -> Result<Json<TableDetails>, ApiError> {
+    tracing::info!(%admin, %table_id, %player_id, "kicking player");
+    command(&state, &table_id, |reply| GameCommand::Kick { player_id, reply }).await
+}
+
+pub async fn ban(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    Path((table_id, player_id)): Path<(String, String)>,
+) -> Result<Json<TableDetails>, ApiError> {
+    tracing::info!(%admin, %table_id, %player_id, "banning player");
+    command(&state, &table_id, |reply| GameCommand::Ban { player_id, reply }).await
+}
+
+pub async fn adjust_stack(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    Path((table_id, player_id)): Path<(String, String)>,
+    request: Result<Json<StackAdjustment>, JsonRejection>,
+) -> Result<Json<TableDetails>, ApiError> {
+    let Json(StackAdjustment { amount, reason }) = request?;
+    command(&state, &table_id, |reply| GameCommand::AdjustStack { player_id, amount, reason, admin, reply }).await
+}
+
+pub async fn void_hand(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    Path(table_id): Path<String>,
+) -> Result<Json<TableDetails>, ApiError> {
+    tracing::info!(%admin, %table_id, "voiding hand");
+    command(&state, &table_id, |reply| GameCommand::VoidHand { reply }).await
+}
+
+pub async fn change_blinds(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    Path(table_id): Path<String>,
+    request: Result<Json<BlindLevel>, JsonRejection>,
+) -> Result<Json<TableDetails>, ApiError> {
+    let Json(blinds) = request?;
+    tracing::info!(%admin, %table_id, ?blinds, "changing blinds");
+    command(&state, &table_id, |reply| GameCommand::ChangeBlinds { blinds, reply }).await
+}
+
+/// Only tables opened through the API; the main table is set up again on every start.
+pub async fn close_table(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    Path(table_id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    if table_id == MAIN_TABLE_ID {
+        return Err(ApiError::conflict("the main table cannot be closed"));
+    }
+    let table = state.tables.get(&table_id).ok_or_else(|| ApiError::not_found("no such table"))?;
+    let (tx, rx) = oneshot::channel();
+    table.commands.send(GameCommand::Close { reply: tx }).await.map_err(|_| ApiError::unavailable("table is closed"))?;
+    rx.await.map_err(|_| ApiError::unavailable("table is closed"))?.map_err(ApiError::conflict)?;
+
+    state.tables.remove(&table_id);
+    tracing::info!(%admin, %table_id, "closed table");
+    Ok(StatusCode::NO_CONTENT)
+}
+
+pub async fn announce(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    request: Result<Json<Announcement>, JsonRejection>,
+) -> Result<StatusCode, ApiError> {
+    let Json(Announcement { message, table_id }) = request?;
+    if message.trim().is_empty() || message.len() > MAX_ANNOUNCEMENT_LEN {
+        return Err(ApiError::bad_request(format!("announcements are 1 to {MAX_ANNOUNCEMENT_LEN} bytes")));
+    }
+
+    let tables = match table_id {
+        Some(table_id) => {
+            let table = state.tables.get(&table_id).ok_or_else(|| ApiError::not_found("no such table"))?;
+            vec![(table_id, table.commands)]
+        }
+        None => state.tables.command_senders(),
+    };
+    tracing::info!(%admin, tables = tables.len(), %message, "announcing");
+    for (_, commands) in tables {
+        let _ = commands.send(GameCommand::Announce { message: message.clone() }).await;
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
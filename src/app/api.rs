@@ -0,0 +1,250 @@
+use axum::{
+    Json,
+    async_trait,
+    extract::{FromRequestParts, Path, Query, State},
+    extract::rejection::{JsonRejection, QueryRejection},
+    http::{StatusCode, header, request::Parts},
+    response::IntoResponse,
+};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::app::{ApiError, AppState, admin::Admin};
+use crate::domain::{
+    Account, HandHistory, LeaderboardEntry, ReconciliationReport, ShuffleVerification, TableConfig, TableDetails,
+    TableSummary,
+};
+use crate::game::fairness;
+use crate::infrastructure::{reconcile, to_pokerstars};
+use crate::shared::{DEFAULT_LEADERBOARD_SIZE, MAX_LEADERBOARD_SIZE};
+
+/// The account behind the request's `Authorization: Bearer` session token.
+pub struct CurrentAccount(pub String);
+
+#[async_trait]
+impl FromRequestParts<AppState> for CurrentAccount {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, Self::Rejection> {
+        let token = parts
+            .headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .ok_or_else(|| ApiError::unauthorized("missing bearer token"))?;
+
+        state.tokens.verify(token.trim()).map(CurrentAccount).map_err(ApiError::unauthorized)
+    }
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct Credentials {
+    username: String,
+    password: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct Session {
+    token: String,
+    expires_at: DateTime<Utc>,
+    account: Account,
+}
+
+pub async fn register(
+    State(state): State<AppState>,
+    request: Result<Json<Credentials>, JsonRejection>,
+) -> Result<(StatusCode, Json<Account>), ApiError> {
+    let Json(request) = request?;
+    let account = state.accounts.register(&request.username, &request.password).await.map_err(ApiError::bad_request)?;
+    Ok((StatusCode::CREATED, Json(account)))
+}
+
+pub async fn login(
+    State(state): State<AppState>,
+    request: Result<Json<Credentials>, JsonRejection>,
+) -> Result<Json<Session>, ApiError> {
+    let Json(request) = request?;
+    let account = state
+        .accounts
+        .authenticate(&request.username, &request.password)
+        .await
+        .map_err(ApiError::unauthorized)?;
+    let (token, expires_at) = state.tokens.issue(&account.id).map_err(ApiError::internal)?;
+
+    Ok(Json(Session { token, expires_at, account }))
+}
+
+pub async fn profile(
+    State(state): State<AppState>,
+    CurrentAccount(account_id): CurrentAccount,
+) -> Result<Json<Account>, ApiError> {
+    state
+        .accounts
+        .account(&account_id)
+        .await
+        .map_err(ApiError::internal)?
+        .map(Json)
+        .ok_or_else(|| ApiError::not_found("account no longer exists"))
+}
+
+pub async fn my_hands(
+    State(state): State<AppState>,
+    CurrentAccount(account_id): CurrentAccount,
+) -> Result<Json<Vec<HandHistory>>, ApiError> {
+    let hands = state.histories.for_player(&account_id).await.map_err(ApiError::internal)?;
+    Ok(Json(hands.into_iter().map(|h| h.seen_by(&account_id)).collect()))
+}
+
+pub async fn list_tables(State(state): State<AppState>) -> Json<Vec<TableSummary>> {
+    Json(state.tables.summaries().await)
+}
+
+pub async fn create_table(
+    State(state): State<AppState>,
+    Admin(admin): Admin,
+    config: Result<Json<TableConfig>, JsonRejection>,
+) -> Result<(StatusCode, Json<TableDetails>), ApiError> {
+    let Json(config) = config?;
+    if *state.draining.borrow() {
+        return Err(ApiError::unavailable("the server is shutting down"));
+    }
+    if config.sit_and_go.is_some() {
+        return Err(ApiError::bad_request("sit-and-go tables are set up by the operator"));
+    }
+
+    let table_id = state.tables.open(config).map_err(ApiError::bad_request)?;
+    tracing::info!(%admin, %table_id, "opened table");
+    let details = state.tables.details(&table_id).await.ok_or_else(|| ApiError::internal("table did not start"))?;
+    Ok((StatusCode::CREATED, Json(details)))
+}
+
+pub async fn table(
+    State(state): State<AppState>,
+    Path(table_id): Path<String>,
+) -> Result<Json<TableDetails>, ApiError> {
+    state.tables.details(&table_id).await.map(Json).ok_or_else(|| ApiError::not_found("no such table"))
+}
+
+pub async fn table_hands(
+    State(state): State<AppState>,
+    CurrentAccount(account_id): CurrentAccount,
+    Path(table_id): Path<String>,
+) -> Result<Json<Vec<HandHistory>>, ApiError> {
+    let hands = state.histories.for_table(&table_id).await.map_err(ApiError::internal)?;
+    Ok(Json(hands.into_iter().map(|h| h.seen_by(&account_id)).collect()))
+}
+
+pub async fn player_hands(
+    State(state): State<AppState>,
+    CurrentAccount(account_id): CurrentAccount,
+    Path(player_id): Path<String>,
+) -> Result<Json<Vec<HandHistory>>, ApiError> {
+    let hands = state.histories.for_player(&player_id).await.map_err(ApiError::internal)?;
+    Ok(Json(hands.into_iter().map(|h| h.seen_by(&account_id)).collect()))
+}
+
+pub async fn hand(
+    State(state): State<AppState>,
+    CurrentAccount(account_id): CurrentAccount,
+    Path(hand_id): Path<String>,
+) -> Result<Json<HandHistory>, ApiError> {
+    state
+        .histories
+        .hand(&hand_id)
+        .await
+        .map_err(ApiError::internal)?
+        .map(|h| Json(h.seen_by(&account_id)))
+        .ok_or_else(|| ApiError::not_found("no such hand"))
+}
+
+/// Only for players dealt into the hand: the proof carries the whole deck.
+pub async fn hand_shuffle(
+    State(state): State<AppState>,
+    CurrentAccount(account_id): CurrentAccount,
+    Path(hand_id): Path<String>,
+) -> Result<Json<ShuffleVerification>, ApiError> {
+    let hand = state
+        .histories
+        .hand(&hand_id)
+        .await
+        .map_err(ApiError::internal)?
+        .filter(|hand| hand.dealt_in(&account_id))
+        .ok_or_else(|| ApiError::not_found("no such hand"))?;
+    let proof = hand.shuffle.ok_or_else(|| ApiError::not_found("hand has no shuffle proof"))?;
+
+    let error = fairness::verify(&proof).err();
+    Ok(Json(ShuffleVerification { hand_id, verified: error.is_none(), error, proof }))
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct LeaderboardQuery {
+    limit: Option<usize>,
+}
+
+pub async fn leaderboard(
+    State(state): State<AppState>,
+    query: Result<Query<LeaderboardQuery>, QueryRejection>,
+) -> Result<Json<Vec<LeaderboardEntry>>, ApiError> {
+    let Query(query) = query?;
+    let limit = query.limit.unwrap_or(DEFAULT_LEADERBOARD_SIZE).clamp(1, MAX_LEADERBOARD_SIZE);
+    state.accounts.leaderboard(limit).await.map(Json).map_err(ApiError::internal)
+}
+
+pub async fn reconciliation(State(state): State<AppState>, Admin(_): Admin) -> Json<ReconciliationReport> {
+    Json(reconcile(&state.tables.command_senders(), &state.accounts).await)
+}
+
+#[derive(Deserialize, JsonSchema)]
+pub struct DateRange {
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+/// Renders the hands as the caller saw them, with the caller as hero.
+pub async fn pokerstars_export(
+    State(state): State<AppState>,
+    CurrentAccount(account_id): CurrentAccount,
+    Path(player_id): Path<String>,
+    range: Result<Query<DateRange>, QueryRejection>,
+) -> Result<impl IntoResponse, ApiError> {
+    let Query(range) = range?;
+    let hands = state
+        .histories
+        .for_player_between(&player_id, range.from, range.to)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let text = hands
+        .into_iter()
+        .map(|h| to_pokerstars(&h.seen_by(&account_id), Some(&account_id)))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    let headers = [
+        (header::CONTENT_TYPE, "text/plain; charset=utf-8"),
+        (header::CONTENT_DISPOSITION, "attachment; filename=\"hand_histories.txt\""),
+    ];
+    Ok((headers, text))
+}
+
+pub async fn metrics(State(state): State<AppState>) -> impl IntoResponse {
+    ([(header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")], state.metrics.render())
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct Health {
+    pub status: String,
+}
+
+/// The process is up and serving requests.
+pub async fn healthz() -> Json<Health> {
+    Json(Health { status: "ok".into() })
+}
+
+/// Whether new players should be sent here: not once the server has started draining.
+pub async fn readyz(State(state): State<AppState>) -> Result<Json<Health>, ApiError> {
+    if *state.draining.borrow() {
+        return Err(ApiError::unavailable("the server is shutting down"));
+    }
+    Ok(Json(Health { status: "ready".into() }))
+}
@@ -0,0 +1,367 @@
+use schemars::JsonSchema;
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{Schema, SchemaObject};
+use serde_json::{Map, Value, json};
+
+use crate::app::admin::{Announcement, StackAdjustment};
+use crate::app::api::{Credentials, DateRange, Health, LeaderboardQuery, Session};
+use crate::app::error::ErrorBody;
+use crate::domain::{
+    Account, BlindLevel, ClientEvent, HandHistory, LeaderboardEntry, ReconciliationReport, ServerEvent, ShuffleVerification, TableConfig,
+    TableDetails, TableSummary,
+};
+use crate::infrastructure::SeatRequest;
+
+const TITLE: &str = "ws_cards_server";
+
+/// The REST surface as OpenAPI 3.0, with every schema generated from the Rust types.
+pub fn openapi() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+    let mut paths = Map::new();
+
+    let bearer = json!([{ "bearer": [] }]);
+    let error = json_content(schema::<ErrorBody>(&mut gen));
+
+    let mut add = |method: &str, path: &str, mut operation: Value| {
+        operation["responses"]["default"] = json!({ "description": "Error", "content": error.clone() });
+        paths.entry(path).or_insert_with(|| json!({}))[method] = operation;
+    };
+
+    add("post", "/accounts", json!({
+        "summary": "Register an account",
+        "requestBody": { "required": true, "content": json_content(schema::<Credentials>(&mut gen)) },
+        "responses": { "201": response("The new account", schema::<Account>(&mut gen)) },
+    }));
+    add("post", "/sessions", json!({
+        "summary": "Log in and get a session token",
+        "requestBody": { "required": true, "content": json_content(schema::<Credentials>(&mut gen)) },
+        "responses": { "200": response("A session token for the account", schema::<Session>(&mut gen)) },
+    }));
+    add("get", "/me", json!({
+        "summary": "The logged-in account and its bankroll",
+        "security": bearer,
+        "responses": { "200": response("The account", schema::<Account>(&mut gen)) },
+    }));
+    add("get", "/me/hands", json!({
+        "summary": "Hands the logged-in account played",
+        "security": bearer,
+        "responses": { "200": response("Hand histories", schema::<Vec<HandHistory>>(&mut gen)) },
+    }));
+    add("get", "/tables", json!({
+        "summary": "List the cash tables",
+        "responses": { "200": response("Every open table", schema::<Vec<TableSummary>>(&mut gen)) },
+    }));
+    add("post", "/tables", json!({
+        "summary": "Open a cash table; operators only",
+        "security": bearer,
+        "requestBody": { "required": true, "content": json_content(schema::<TableConfig>(&mut gen)) },
+        "responses": { "201": response("The new table", schema::<TableDetails>(&mut gen)) },
+    }));
+    add("get", "/tables/{table_id}", json!({
+        "summary": "A table's configuration and current state",
+        "parameters": [path_param("table_id")],
+        "responses": { "200": response("The table", schema::<TableDetails>(&mut gen)) },
+    }));
+    add("get", "/tables/{table_id}/hands", json!({
+        "summary": "Hands played at a table, with hole cards only where shown down or the caller's own",
+        "security": bearer,
+        "parameters": [path_param("table_id")],
+        "responses": { "200": response("Hand histories", schema::<Vec<HandHistory>>(&mut gen)) },
+    }));
+    add("get", "/players/{player_id}/hands", json!({
+        "summary": "Hands a player played, with hole cards only where shown down or the caller's own",
+        "security": bearer,
+        "parameters": [path_param("player_id")],
+        "responses": { "200": response("Hand histories", schema::<Vec<HandHistory>>(&mut gen)) },
+    }));
+    let mut export_params = vec![path_param("player_id")];
+    export_params.extend(query_params::<DateRange>(&mut gen));
+    add("get", "/players/{player_id}/hands/pokerstars", json!({
+        "summary": "A player's hands as a PokerStars text export, with the caller as hero",
+        "security": bearer,
+        "parameters": export_params,
+        "responses": {
+            "200": { "description": "Hand histories", "content": { "text/plain": { "schema": { "type": "string" } } } },
+        },
+    }));
+    add("get", "/hands/{hand_id}", json!({
+        "summary": "One hand history, with hole cards only where shown down or the caller's own",
+        "security": bearer,
+        "parameters": [path_param("hand_id")],
+        "responses": { "200": response("The hand", schema::<HandHistory>(&mut gen)) },
+    }));
+    add("get", "/hands/{hand_id}/shuffle", json!({
+        "summary": "Check a hand's revealed shuffle against its commitment; only for players dealt in",
+        "security": bearer,
+        "parameters": [path_param("hand_id")],
+        "responses": { "200": response("The proof and whether it holds", schema::<ShuffleVerification>(&mut gen)) },
+    }));
+    add("get", "/leaderboard", json!({
+        "summary": "Accounts with the largest bankrolls",
+        "parameters": query_params::<LeaderboardQuery>(&mut gen),
+        "responses": { "200": response("Ranked bankrolls", schema::<Vec<LeaderboardEntry>>(&mut gen)) },
+    }));
+    add("get", "/ledger/reconciliation", json!({
+        "summary": "Check every table's chips against the ledger; operators only",
+        "security": bearer,
+        "responses": { "200": response("The reconciliation report", schema::<ReconciliationReport>(&mut gen)) },
+    }));
+    add("get", "/metrics", json!({
+        "summary": "Server metrics for Prometheus",
+        "responses": {
+            "200": { "description": "Metrics in the Prometheus text format", "content": { "text/plain": { "schema": { "type": "string" } } } },
+        },
+    }));
+    add("get", "/healthz", json!({
+        "summary": "Whether the server is up",
+        "responses": { "200": response("The server is up", schema::<Health>(&mut gen)) },
+    }));
+    add("get", "/readyz", json!({
+        "summary": "Whether the server takes new players; 503 once it starts shutting down",
+        "responses": { "200": response("The server is ready", schema::<Health>(&mut gen)) },
+    }));
+    for (path, summary) in [
+        ("/admin/tables/{table_id}/pause", "Pause a table: no actions are taken and no hands dealt"),
+        ("/admin/tables/{table_id}/resume", "Resume a paused table"),
+        ("/admin/tables/{table_id}/void", "Call off the hand in progress and return every chip committed to it"),
+    ] {
+        add("post", path, json!({
+            "summary": summary,
+            "security": bearer,
+            "parameters": [path_param("table_id")],
+            "responses": { "200": response("The table", schema::<TableDetails>(&mut gen)) },
+        }));
+    }
+    add("post", "/admin/tables/{table_id}/blinds", json!({
+        "summary": "Change a cash table's blinds, from the next hand if one is in progress",
+        "security": bearer,
+        "parameters": [path_param("table_id")],
+        "requestBody": { "required": true, "content": json_content(schema::<BlindLevel>(&mut gen)) },
+        "responses": { "200": response("The table", schema::<TableDetails>(&mut gen)) },
+    }));
+    add("post", "/admin/tables/{table_id}/close", json!({
+        "summary": "Cash every player out of a table opened through the API and take it down; not while a hand is in progress",
+        "security": bearer,
+        "parameters": [path_param("table_id")],
+        "responses": { "204": { "description": "The table was closed" } },
+    }));
+    for (path, summary) in [
+        ("/admin/tables/{table_id}/players/{player_id}/kick", "Stand a player up and cash them out"),
+        ("/admin/tables/{table_id}/players/{player_id}/ban", "Kick a player and keep them from sitting at the table again"),
+    ] {
+        add("post", path, json!({
+            "summary": summary,
+            "security": bearer,
+            "parameters": [path_param("table_id"), path_param("player_id")],
+            "responses": { "200": response("The table", schema::<TableDetails>(&mut gen)) },
+        }));
+    }
+    add("post", "/admin/tables/{table_id}/players/{player_id}/stack", json!({
+        "summary": "Add chips to or take chips from a cash player's stack between hands, giving a reason",
+        "security": bearer,
+        "parameters": [path_param("table_id"), path_param("player_id")],
+        "requestBody": { "required": true, "content": json_content(schema::<StackAdjustment>(&mut gen)) },
+        "responses": { "200": response("The table", schema::<TableDetails>(&mut gen)) },
+    }));
+    add("post", "/admin/announcements", json!({
+        "summary": "Broadcast a message to one cash table or all of them",
+        "security": bearer,
+        "requestBody": { "required": true, "content": json_content(schema::<Announcement>(&mut gen)) },
+        "responses": { "204": { "description": "The announcement was sent" } },
+    }));
+    add("get", "/openapi.json", json!({
+        "summary": "This document",
+        "responses": { "200": response("OpenAPI document", json!({ "type": "object" })) },
+    }));
+    add("get", "/asyncapi.json", json!({
+        "summary": "The WebSocket protocol as an AsyncAPI document",
+        "responses": { "200": response("AsyncAPI document", json!({ "type": "object" })) },
+    }));
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": TITLE, "version": env!("CARGO_PKG_VERSION") },
+        "paths": paths,
+        "components": {
+            "schemas": gen.take_definitions(),
+            "securitySchemes": {
+                "bearer": { "type": "http", "scheme": "bearer", "bearerFormat": "JWT" },
+            },
+        },
+    })
+}
+
+/// The WebSocket protocol as AsyncAPI 2.6: one message per `ServerEvent` and `ClientEvent` variant.
+pub fn asyncapi() -> Value {
+    let mut gen = SchemaSettings::draft07()
+        .with(|settings| settings.definitions_path = "#/components/schemas/".into())
+        .into_generator();
+    let mut messages = Map::new();
+
+    let server = tagged_messages::<ServerEvent>(&mut gen, "type", &mut messages);
+    let client = tagged_messages::<ClientEvent>(&mut gen, "action", &mut messages);
+    let query = serde_json::to_value(gen.root_schema_for::<SeatRequest>().schema).expect("schemas serialize");
+
+    let channel = |description: &str| json!({
+        "description": description,
+        "bindings": { "ws": { "method": "GET", "query": query } },
+        "subscribe": { "summary": "Events the server sends", "message": { "oneOf": server } },
+        "publish": { "summary": "Requests the client sends", "message": { "oneOf": client } },
+    });
+
+    json!({
+        "asyncapi": "2.6.0",
+        "info": { "title": TITLE, "version": env!("CARGO_PKG_VERSION") },
+        "defaultContentType": "application/json",
+        "channels": {
+            "/ws": channel("A seat at a cash table, bought in from the account's bankroll. `table_id` defaults to the main table."),
+            "/tournament/ws": channel("A tournament entry; the session follows the player from table to table."),
+        },
+        "components": {
+            "messages": messages,
+            "schemas": gen.take_definitions(),
+        },
+    })
+}
+
+pub fn render(doc: &Value) -> String {
+    let mut text = serde_json::to_string_pretty(doc).expect("documents serialize");
+    text.push('\n');
+    text
+}
+
+fn schema<T: JsonSchema>(gen: &mut SchemaGenerator) -> Value {
+    serde_json::to_value(gen.subschema_for::<T>()).expect("schemas serialize")
+}
+
+fn json_content(schema: Value) -> Value {
+    json!({ "application/json": { "schema": schema } })
+}
+
+fn response(description: &str, schema: Value) -> Value {
+    json!({ "description": description, "content": json_content(schema) })
+}
+
+fn path_param(name: &str) -> Value {
+    json!({ "name": name, "in": "path", "required": true, "schema": { "type": "string" } })
+}
+
+/// One query parameter per field of a `Query` extractor's type.
+fn query_params<T: JsonSchema>(gen: &mut SchemaGenerator) -> Vec<Value> {
+    let root = gen.root_schema_for::<T>().schema;
+    let Some(object) = root.object else { return vec![] };
+
+    object
+        .properties
+        .iter()
+        .map(|(name, schema)| json!({
+            "name": name,
+            "in": "query",
+            "required": object.required.contains(name),
+            "schema": schema,
+        }))
+        .collect()
+}
+
+/// Splits a tagged enum into one message per variant, named by its tag.
+fn tagged_messages<T: JsonSchema>(gen: &mut SchemaGenerator, tag: &str, messages: &mut Map<String, Value>) -> Vec<Value> {
+    gen.subschema_for::<T>();
+    let Some(Schema::Object(definition)) = gen.definitions().get(&T::schema_name()).cloned() else {
+        return vec![];
+    };
+    let variants = definition.subschemas.and_then(|s| s.one_of).unwrap_or_default();
+
+    let mut refs = vec![];
+    for variant in variants {
+        let Schema::Object(variant) = variant else { continue };
+        let Some(name) = tag_value(&variant, tag) else { continue };
+
+        let payload = serde_json::to_value(&variant).expect("schemas serialize");
+        messages.insert(name.clone(), json!({ "name": name, "title": name, "payload": payload }));
+        refs.push(json!({ "$ref": format!("#/components/messages/{name}") }));
+    }
+    refs
+}
+
+fn tag_value(variant: &SchemaObject, tag: &str) -> Option<String> {
+    let object = variant.object.as_ref()?;
+    let Some(Schema::Object(tag_schema)) = object.properties.get(tag) else { return None };
+    tag_schema.enum_values.as_ref()?.first()?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use axum::body::Body;
+    use axum::http::{Method, Request, StatusCode};
+    use tokio::sync::mpsc;
+    use tower::ServiceExt;
+
+    use super::*;
+    use crate::app::{AppState, create_routes};
+    use crate::game::{TableRecorder, TableRegistry};
+    use crate::infrastructure::{AccountStore, HandHistoryStore, Metrics, SessionTokens};
+    use crate::shared::{API_DOCS_COMMAND, DEFAULT_API_DOCS_DIR};
+
+    #[test]
+    fn checked_in_docs_match_the_code() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(DEFAULT_API_DOCS_DIR);
+        for (file, doc) in [("openapi.json", openapi()), ("asyncapi.json", asyncapi())] {
+            let on_disk = std::fs::read_to_string(dir.join(file)).unwrap_or_default();
+            assert!(on_disk == render(&doc), "{file} is out of date; run `cargo run -- {API_DOCS_COMMAND}`");
+        }
+    }
+
+    #[test]
+    fn every_message_variant_is_documented() {
+        let doc = asyncapi();
+        let messages = doc["components"]["messages"].as_object().unwrap();
+        for name in ["RoundStarted", "GameState", "Tournament", "Error", "fold", "allin", "run_boards", "propose_deal"] {
+            assert!(messages.contains_key(name), "{name} missing from the AsyncAPI document");
+        }
+        for message in messages.values() {
+            assert!(message["payload"].is_object());
+        }
+    }
+
+    #[tokio::test]
+    async fn every_documented_path_is_routed() {
+        let recorder = TableRecorder {
+            histories: mpsc::unbounded_channel().0,
+            log: mpsc::unbounded_channel().0,
+            snapshots: mpsc::unbounded_channel().0,
+            ledger: mpsc::unbounded_channel().0,
+            metrics: Metrics::default(),
+        };
+        let state = AppState {
+            metrics: recorder.metrics.clone(),
+            draining: tokio::sync::watch::channel(false).1,
+            tables: TableRegistry::new(recorder),
+            histories: HandHistoryStore::open(std::env::temp_dir().join("api-docs-test-missing.jsonl")).unwrap(),
+            accounts: AccountStore::open(":memory:").unwrap(),
+            tokens: SessionTokens::ephemeral(),
+            admins: Default::default(),
+        };
+        let router = create_routes(state, None);
+
+        let doc = openapi();
+        for (path, operations) in doc["paths"].as_object().unwrap() {
+            for method in operations.as_object().unwrap().keys() {
+                let uri = path.replace(['{', '}'], "");
+                let request = Request::builder()
+                    .method(Method::from_bytes(method.to_uppercase().as_bytes()).unwrap())
+                    .uri(&uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from("{}"))
+                    .unwrap();
+                let response = router.clone().oneshot(request).await.unwrap();
+                let status = response.status();
+
+                // The router's own 404 has no body; a handler's 404 carries an error document.
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                let unrouted = status == StatusCode::METHOD_NOT_ALLOWED || (status == StatusCode::NOT_FOUND && body.is_empty());
+                assert!(!unrouted, "{method} {path} is documented but not routed");
+            }
+        }
+    }
+}
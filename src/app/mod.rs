@@ -1,3 +1,8 @@
 pub mod routes;
+pub mod api;
+pub mod admin;
+pub mod error;
+pub mod docs;
 
 pub use routes::*;
+pub use error::ApiError;
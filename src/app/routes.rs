@@ -1,20 +1,85 @@
-use axum::{Router, routing::get};
-use tokio::sync::{mpsc, broadcast};
+use std::collections::HashSet;
+use std::sync::Arc;
+use axum::{
+    Json, Router,
+    extract::{Query, State},
+    routing::{get, post},
+};
+use tokio::sync::{mpsc, broadcast, watch};
 
-use crate::domain::{ServerEvent, GameCommand};
-use crate::infrastructure::ws_handler;
+use crate::app::{ApiError, admin, api, docs};
+use crate::domain::{DirectorCommand, TournamentEvent};
+use crate::game::TableRegistry;
+use crate::infrastructure::{ws_handler, tournament_ws_handler, AccountStore, HandHistoryStore, Metrics, SeatRequest, SessionTokens};
+
+#[derive(Clone)]
+pub struct AppState {
+    pub tables: TableRegistry,
+    pub histories: HandHistoryStore,
+    pub accounts: AccountStore,
+    pub tokens: SessionTokens,
+    pub metrics: Metrics,
+    /// Set once the server starts shutting down.
+    pub draining: watch::Receiver<bool>,
+    /// Account ids allowed to use the `/admin` endpoints.
+    pub admins: Arc<HashSet<String>>,
+}
 
 pub fn create_routes(
-    manager_tx: mpsc::Sender<GameCommand>,
-    broadcaster: broadcast::Sender<ServerEvent>,
+    state: AppState,
+    director: Option<(mpsc::Sender<DirectorCommand>, broadcast::Sender<TournamentEvent>)>,
 ) -> Router {
-    Router::new().route(
-        "/ws",
-        get({
-            let manager_tx = manager_tx.clone();
-            let broadcaster = broadcaster.clone();
-            
-            move |ws| ws_handler(ws, manager_tx, broadcaster)
-        }),
-    )
+    let router = Router::new()
+        .route(
+            "/ws",
+            get(|State(state): State<AppState>, ws, Query(request): Query<SeatRequest>| async move {
+                if *state.draining.borrow() {
+                    return Err(ApiError::unavailable("the server is shutting down"));
+                }
+                ws_handler(ws, request, state.tables, state.accounts, state.tokens, state.metrics).await.map_err(ApiError::from)
+            }),
+        )
+        .route("/accounts", post(api::register))
+        .route("/sessions", post(api::login))
+        .route("/me", get(api::profile))
+        .route("/me/hands", get(api::my_hands))
+        .route("/tables", get(api::list_tables).post(api::create_table))
+        .route("/tables/:table_id", get(api::table))
+        .route("/tables/:table_id/hands", get(api::table_hands))
+        .route("/players/:player_id/hands", get(api::player_hands))
+        .route("/players/:player_id/hands/pokerstars", get(api::pokerstars_export))
+        .route("/hands/:hand_id", get(api::hand))
+        .route("/hands/:hand_id/shuffle", get(api::hand_shuffle))
+        .route("/leaderboard", get(api::leaderboard))
+        .route("/ledger/reconciliation", get(api::reconciliation))
+        .route("/metrics", get(api::metrics))
+        .route("/healthz", get(api::healthz))
+        .route("/readyz", get(api::readyz))
+        .route("/admin/tables/:table_id/pause", post(admin::pause))
+        .route("/admin/tables/:table_id/resume", post(admin::resume))
+        .route("/admin/tables/:table_id/void", post(admin::void_hand))
+        .route("/admin/tables/:table_id/blinds", post(admin::change_blinds))
+        .route("/admin/tables/:table_id/close", post(admin::close_table))
+        .route("/admin/tables/:table_id/players/:player_id/kick", post(admin::kick))
+        .route("/admin/tables/:table_id/players/:player_id/ban", post(admin::ban))
+        .route("/admin/tables/:table_id/players/:player_id/stack", post(admin::adjust_stack))
+        .route("/admin/announcements", post(admin::announce))
+        .route("/openapi.json", get(|| async { Json(docs::openapi()) }))
+        .route("/asyncapi.json", get(|| async { Json(docs::asyncapi()) }));
+
+    let router = match director {
+        Some((director_tx, director_events)) => router.route(
+            "/tournament/ws",
+            get(move |State(state): State<AppState>, ws, Query(request): Query<SeatRequest>| async move {
+                if *state.draining.borrow() {
+                    return Err(ApiError::unavailable("the server is shutting down"));
+                }
+                tournament_ws_handler(ws, request, director_tx, director_events, state.tokens, state.metrics)
+                    .await
+                    .map_err(ApiError::from)
+            }),
+        ),
+        None => router,
+    };
+    router.with_state(state)
 }
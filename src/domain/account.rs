@@ -0,0 +1,28 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use schemars::JsonSchema;
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct Account {
+    pub id: String,
+    pub username: String,
+    pub bankroll: u64,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub username: String,
+    pub bankroll: u64,
+}
+
+/// Chips a player took off a table, to be returned to their bankroll.
+#[derive(Debug, Clone)]
+pub struct CashOut {
+    pub account_id: String,
+    pub table_id: String,
+    pub amount: u64,
+    /// The buy-in these chips started from, closed out of the account's open buy-ins on the table.
+    pub bought: u64,
+}
@@ -1,13 +1,22 @@
 use std::collections::HashMap;
-use crate::domain::Card;
+use serde::{Serialize, Deserialize};
+use crate::domain::{Card, ShuffleProof};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct CardStore {
-    pub hands: HashMap<String, [Card; 2]>,
+    pub hands: HashMap<String, Vec<Card>>,
     pub deck: Vec<Card>,
+    pub discards: Vec<Card>,
+    /// How the hand's deck was shuffled; secret until the hand is over.
+    #[serde(default)]
+    pub shuffle: Option<ShuffleProof>,
+    /// Times the discards went back into the deck this hand.
+    #[serde(default)]
+    pub reshuffles: u32,
 }
 
 impl CardStore {
     pub fn new() -> Self {
-        Self { hands: Default::default(), deck: Vec::new() }
+        Self { hands: Default::default(), deck: Vec::new(), discards: Vec::new(), shuffle: None, reshuffles: 0 }
     }
 }
@@ -1,6 +1,7 @@
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, Copy, JsonSchema)]
 pub enum Suit {
     Hearts,
     Diamonds,
@@ -8,7 +9,7 @@ pub enum Suit {
     Spades,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, JsonSchema)]
 pub enum Rank {
     Two,
     Three,
@@ -25,8 +26,71 @@ pub enum Rank {
     Ace,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+impl Rank {
+    pub fn value(self) -> u8 {
+        self as u8 + 2
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash, JsonSchema)]
 pub struct Card {
     pub rank: Rank,
     pub suit: Suit,
 }
+
+/// Reads the short notation written by `Display`.
+impl std::str::FromStr for Card {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        let (Some(rank), Some(suit), None) = (chars.next(), chars.next(), chars.next()) else {
+            return Err(format!("bad card {s:?}"));
+        };
+        let rank = match rank {
+            'T' => Rank::Ten,
+            'J' => Rank::Jack,
+            'Q' => Rank::Queen,
+            'K' => Rank::King,
+            'A' => Rank::Ace,
+            '2'..='9' => [Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six, Rank::Seven, Rank::Eight, Rank::Nine]
+                [rank as usize - '2' as usize],
+            _ => return Err(format!("bad rank in {s:?}")),
+        };
+        let suit = match suit {
+            'h' => Suit::Hearts,
+            'd' => Suit::Diamonds,
+            'c' => Suit::Clubs,
+            's' => Suit::Spades,
+            _ => return Err(format!("bad suit in {s:?}")),
+        };
+        Ok(Card { rank, suit })
+    }
+}
+
+/// Space-separated short notation, e.g. `"Ah Kd"`.
+#[cfg(test)]
+pub fn parse_cards(text: &str) -> Vec<Card> {
+    text.split_whitespace().map(|card| card.parse().unwrap()).collect()
+}
+
+/// Short notation used by hand history formats, e.g. `Ah`, `Td`, `2c`.
+impl std::fmt::Display for Card {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let rank = match self.rank {
+            Rank::Ten => 'T',
+            Rank::Jack => 'J',
+            Rank::Queen => 'Q',
+            Rank::King => 'K',
+            Rank::Ace => 'A',
+            rank => char::from(b'0' + rank.value()),
+        };
+        let suit = match self.suit {
+            Suit::Hearts => 'h',
+            Suit::Diamonds => 'd',
+            Suit::Clubs => 'c',
+            Suit::Spades => 's',
+        };
+        write!(f, "{rank}{suit}")
+    }
+}
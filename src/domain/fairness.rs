@@ -0,0 +1,34 @@
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+
+use crate::domain::Card;
+
+/// Everything needed to recompute a hand's shuffle, revealed once the hand is over.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ShuffleProof {
+    /// SHA-256 of the server seed, published one hand before this hand was dealt.
+    pub commitment: String,
+    /// 32 random bytes, hex encoded.
+    pub server_seed: String,
+    /// The seeds of the players dealt in, in seat order.
+    pub client_seeds: Vec<ClientSeed>,
+    /// SHA-256 of `deck`, the server seed and the SHA-256 of each client seed.
+    pub deck_seed: String,
+    /// The deck after the shuffle; cards are dealt from the end.
+    pub deck: Vec<Card>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClientSeed {
+    pub player_id: String,
+    pub seed: String,
+}
+
+#[derive(Serialize, JsonSchema)]
+pub struct ShuffleVerification {
+    pub hand_id: String,
+    pub verified: bool,
+    /// Why the proof does not hold up, when it does not.
+    pub error: Option<String>,
+    pub proof: ShuffleProof,
+}
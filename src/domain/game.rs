@@ -1,41 +1,116 @@
 use serde::{Serialize, Deserialize};
-use std::collections::HashMap;
-use tokio::sync::{oneshot};
+use schemars::JsonSchema;
+use tokio::sync::{broadcast, mpsc, oneshot};
 
-use crate::domain::{Card, PlayerAction, PublicPlayer, PrivateState};
-use crate::shared::INITIAL_HAND_SIZE;
+use crate::domain::{
+    BettingStructure, BlindLevel, Card, DealAction, GameVariant, PlayerAction, PublicPlayer,
+    PrivateState, Purchase, ReleasedPlayer, ServerEvent, TableReconciliation,
+};
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PublicGameState {
+    pub variant: GameVariant,
+    pub betting_structure: BettingStructure,
     pub players: Vec<PublicPlayer>,
     pub community_cards: Vec<Card>,
+    pub boards: Vec<Vec<Card>>,
     pub pot: u64,
     pub dealer_seat: usize,
     pub current_turn_seat: Option<usize>,
     pub phase: RoundPhase,
     pub small_blind_amount: u64,
     pub big_blind_amount: u64,
+    pub ante_amount: u64,
     pub current_bet: u64,
+    /// SHA-256 of the server seed for the next hand dealt.
+    #[serde(default)]
+    pub next_shuffle_commitment: String,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub enum RoundPhase {
     Waiting,
     Preflop,
     Flop,
     Turn,
     River,
+    Predraw,
+    Draw { round: u8 },
+    PostDraw { round: u8 },
+    RunoutVote,
     Showdown,
 }
 
-pub struct CardStore {
-    pub hands: HashMap<String, [Card; INITIAL_HAND_SIZE]>,
-    pub deck: Vec<Card>,
+impl RoundPhase {
+    pub fn is_betting(&self) -> bool {
+        !matches!(
+            self,
+            RoundPhase::Waiting | RoundPhase::Draw { .. } | RoundPhase::RunoutVote | RoundPhase::Showdown
+        )
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PotAward {
+    pub pot_index: usize,
+    pub board: usize,
+    pub seat: usize,
+    pub amount: u64,
 }
 
 pub enum GameCommand {
-    Join { reply: oneshot::Sender<Result<String, String>> },
+    /// Seats an account with the `stack` it bought in for from its bankroll.
+    Join { account_id: String, stack: u64, reply: oneshot::Sender<Result<String, String>> },
     Action { player_id: String, action: PlayerAction, reply: oneshot::Sender<Result<(), String>> },
     Disconnect { player_id: String },
+    /// A player's socket is back. Clears their away flag; `false` when they hold no seat here.
+    Reconnect { player_id: String, reply: oneshot::Sender<Result<bool, String>> },
     GetPrivateState { player_id: String, reply: oneshot::Sender<PrivateState> },
+    GetState { reply: oneshot::Sender<PublicGameState> },
+    /// A seated player's stack with any chips bought for the next hand; `None` when not seated.
+    PlayerChips { player_id: String, reply: oneshot::Sender<Option<u64>> },
+    SeatPlayer { player_id: String, stack: u64, away: bool, reply: oneshot::Sender<Result<usize, String>> },
+    ReleasePlayer { reply: oneshot::Sender<Result<ReleasedPlayer, String>> },
+    SetBlinds { blinds: BlindLevel },
+    StartHand { reply: oneshot::Sender<bool> },
+    /// Tournament chips bought mid-event; added before the player's next hand.
+    AddChips { player_id: String, amount: u64 },
+    Purchase { player_id: String, purchase: Purchase, reply: oneshot::Sender<Result<(), String>> },
+    Deal { player_id: String, action: DealAction, reply: oneshot::Sender<Result<(), String>> },
+    SetClientSeed { player_id: String, seed: String, reply: oneshot::Sender<Result<(), String>> },
+    /// Checks the table's chips against the ledger; the reply arrives once the ledger catches up.
+    Reconcile { reply: oneshot::Sender<TableReconciliation> },
+    /// Stops seating players and dealing hands ahead of a shutdown. The reply comes once the hand
+    /// in progress is over and the table's state is saved.
+    Drain { reply: oneshot::Sender<()> },
+    /// Operator controls. A paused table takes no actions and deals no hands until resumed.
+    Pause { reply: oneshot::Sender<Result<(), String>> },
+    Resume { reply: oneshot::Sender<Result<(), String>> },
+    /// Stands a player up and cashes them out, as if they had left.
+    Kick { player_id: String, reply: oneshot::Sender<Result<(), String>> },
+    /// Kicks the player if seated and keeps them from sitting at the table again.
+    Ban { player_id: String, reply: oneshot::Sender<Result<(), String>> },
+    /// Adds `amount` chips to a seated player's stack, or takes them when negative. Replies with
+    /// the new stack.
+    AdjustStack {
+        player_id: String,
+        amount: i64,
+        reason: String,
+        admin: String,
+        reply: oneshot::Sender<Result<u64, String>>,
+    },
+    /// Calls off the hand in progress and gives back every chip committed to it.
+    VoidHand { reply: oneshot::Sender<Result<(), String>> },
+    Announce { message: String },
+    /// New blinds for a cash table: at once between hands, otherwise from the next hand.
+    ChangeBlinds { blinds: BlindLevel, reply: oneshot::Sender<Result<(), String>> },
+    /// Cashes every player out of an idle cash table and stops it.
+    Close { reply: oneshot::Sender<Result<(), String>> },
+}
+
+#[derive(Clone)]
+pub struct TableHandle {
+    pub table_id: usize,
+    pub commands: mpsc::Sender<GameCommand>,
+    pub events: broadcast::Sender<ServerEvent>,
 }
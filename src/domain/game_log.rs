@@ -0,0 +1,61 @@
+use serde::{Serialize, Deserialize};
+
+use crate::domain::{BlindLevel, Card, DealAction, PlayerAction, Purchase, TableConfig};
+
+/// One accepted command in a table's event log. Replaying a table's entries in order into a fresh
+/// `GameManager` rebuilds the exact same table, including every shuffle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GameLogEntry {
+    pub table_id: String,
+    pub sequence: u64,
+    /// Time since the table opened, so blind clocks replay identically.
+    pub elapsed_ns: u64,
+    pub command: LoggedCommand,
+}
+
+/// A key for a table's RNG. Seeded test tables, and logs written before tables took 32-byte
+/// keys, hold a `u64`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RngSeed {
+    Key([u8; 32]),
+    Number(u64),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LoggedCommand {
+    Opened { config: Box<TableConfig>, seed: RngSeed, managed: Option<usize> },
+    Join { player_id: String, stack: u64, banked: bool },
+    Action { player_id: String, action: PlayerAction },
+    Disconnect { player_id: String },
+    Reconnect { player_id: String },
+    /// The server restarted: every seated player is away until they reconnect.
+    Restart,
+    SeatPlayer { player_id: String, stack: u64, away: bool },
+    ReleasePlayer,
+    SetBlinds { blinds: BlindLevel },
+    StartHand,
+    AddChips { player_id: String, amount: u64 },
+    Purchase { player_id: String, purchase: Purchase },
+    Deal { player_id: String, action: DealAction },
+    ClockTick,
+    /// The table rekeyed its RNG from the operating system.
+    Reseed { seed: RngSeed },
+    /// The hand in progress was called off, after a restart or by an operator, and its bets
+    /// returned.
+    VoidHand,
+    ClientSeed { player_id: String, seed: String },
+    StackDeck { cards: Vec<Card> },
+    /// The server began shutting down; no hand was dealt after this.
+    Drain,
+    Pause,
+    Resume,
+    Kick { player_id: String },
+    Ban { player_id: String },
+    /// An operator's correction to a stack, with who made it and why.
+    AdjustStack { player_id: String, amount: i64, reason: String, admin: String },
+    ChangeBlinds { blinds: BlindLevel },
+    /// An operator closed the table; it is not recovered after a restart.
+    Close,
+}
@@ -0,0 +1,121 @@
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+
+use crate::domain::{
+    BettingStructure, Card, GameVariant, PlayerAction, PotAward, RevealedHand, RoundPhase, ShuffleProof,
+};
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct HandHistory {
+    pub hand_id: String,
+    pub table_id: String,
+    pub hand_number: u64,
+    #[serde(default)]
+    pub tournament: bool,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+    pub variant: GameVariant,
+    pub betting_structure: BettingStructure,
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub ante: u64,
+    pub button_seat: usize,
+    pub seats: Vec<SeatRecord>,
+    pub actions: Vec<ActionRecord>,
+    pub boards: Vec<Vec<Card>>,
+    pub showdown: Vec<RevealedHand>,
+    pub winners: Vec<PotAward>,
+    pub rake: u64,
+    #[serde(default)]
+    pub shuffle: Option<ShuffleProof>,
+}
+
+impl HandHistory {
+    /// The hand as `viewer` may see it: hole cards only for their own seat and for hands shown
+    /// down, and no shuffle proof, whose deck would give away the rest.
+    pub fn seen_by(mut self, viewer: &str) -> Self {
+        for seat in &mut self.seats {
+            if seat.player_id != viewer && !self.showdown.iter().any(|shown| shown.seat == seat.seat) {
+                seat.cards.clear();
+            }
+        }
+        self.shuffle = None;
+        self
+    }
+
+    pub fn dealt_in(&self, player_id: &str) -> bool {
+        self.seats.iter().any(|seat| seat.player_id == player_id)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SeatRecord {
+    pub seat: usize,
+    pub player_id: String,
+    /// Chips at the start of the hand.
+    pub stack: u64,
+    /// The final holding, after any draws.
+    pub cards: Vec<Card>,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ActionRecord {
+    pub phase: RoundPhase,
+    pub seat: usize,
+    pub player_id: String,
+    pub action: RecordedAction,
+    /// Chips put in by this action.
+    pub amount: u64,
+    /// The player's total bet on the street afterwards.
+    pub total_bet: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RecordedAction {
+    Ante,
+    SmallBlind,
+    BigBlind,
+    Fold,
+    Check,
+    Call,
+    Bet,
+    Raise,
+    AllIn,
+    Draw { discarded: usize },
+    RunBoards { boards: u8 },
+}
+
+impl RecordedAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RecordedAction::Ante => "ante",
+            RecordedAction::SmallBlind => "small_blind",
+            RecordedAction::BigBlind => "big_blind",
+            RecordedAction::Fold => "fold",
+            RecordedAction::Check => "check",
+            RecordedAction::Call => "call",
+            RecordedAction::Bet => "bet",
+            RecordedAction::Raise => "raise",
+            RecordedAction::AllIn => "all_in",
+            RecordedAction::Draw { .. } => "draw",
+            RecordedAction::RunBoards { .. } => "run_boards",
+        }
+    }
+}
+
+impl From<&PlayerAction> for RecordedAction {
+    fn from(action: &PlayerAction) -> Self {
+        match action {
+            PlayerAction::Fold => RecordedAction::Fold,
+            PlayerAction::Check => RecordedAction::Check,
+            PlayerAction::Call => RecordedAction::Call,
+            PlayerAction::Bet { .. } => RecordedAction::Bet,
+            PlayerAction::Raise { .. } => RecordedAction::Raise,
+            PlayerAction::AllIn => RecordedAction::AllIn,
+            PlayerAction::Draw { discard } => RecordedAction::Draw { discarded: discard.len() },
+            PlayerAction::RunBoards { boards } => RecordedAction::RunBoards { boards: *boards },
+        }
+    }
+}
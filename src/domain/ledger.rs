@@ -0,0 +1,198 @@
+use std::fmt::{Display, Formatter};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use tokio::sync::oneshot;
+
+use crate::domain::CashOut;
+
+/// Somewhere chips can be: a bankroll, a seat, a pot, or one of the house accounts chips are
+/// created in or leave through.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LedgerAccount {
+    Bankroll { account_id: String },
+    Stack { table_id: String, player_id: String },
+    Pot { table_id: String },
+    GuestChips,
+    TournamentChips,
+    Rake,
+    WriteOff,
+    /// Where operator corrections to stacks come from and go to.
+    Adjustments,
+    /// Tournament entry fees, held until they are paid out as prizes.
+    PrizePool,
+}
+
+impl LedgerAccount {
+    /// Prefix shared by every account holding chips on a table.
+    pub fn table_prefix(table_id: &str) -> String {
+        format!("table:{table_id}:")
+    }
+}
+
+impl Display for LedgerAccount {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LedgerAccount::Bankroll { account_id } => write!(f, "bankroll:{account_id}"),
+            LedgerAccount::Stack { table_id, player_id } => write!(f, "{}stack:{player_id}", Self::table_prefix(table_id)),
+            LedgerAccount::Pot { table_id } => write!(f, "{}pot", Self::table_prefix(table_id)),
+            LedgerAccount::GuestChips => write!(f, "house:guest_chips"),
+            LedgerAccount::TournamentChips => write!(f, "house:tournament_chips"),
+            LedgerAccount::Rake => write!(f, "house:rake"),
+            LedgerAccount::WriteOff => write!(f, "house:write_off"),
+            LedgerAccount::Adjustments => write!(f, "house:adjustments"),
+            LedgerAccount::PrizePool => write!(f, "house:prize_pool"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalKind {
+    BuyIn,
+    CashOut,
+    Blind,
+    Ante,
+    Bet,
+    PotAward,
+    Rake,
+    VoidRefund,
+    /// The part of a bet nobody called, handed back before the pot is awarded.
+    Uncalled,
+    /// Guest or tournament chips put into play.
+    Issue,
+    /// Guest or tournament chips taken out of play.
+    Retire,
+    /// Tournament chips moving between tables.
+    Transfer,
+    WriteOff,
+    /// An operator's correction to a stack.
+    Adjustment,
+    /// A tournament entry fee paid from a bankroll, or refunded to it.
+    EntryFee,
+    /// A tournament prize paid from the prize pool.
+    Prize,
+}
+
+impl JournalKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JournalKind::BuyIn => "buy_in",
+            JournalKind::CashOut => "cash_out",
+            JournalKind::Blind => "blind",
+            JournalKind::Ante => "ante",
+            JournalKind::Bet => "bet",
+            JournalKind::PotAward => "pot_award",
+            JournalKind::Rake => "rake",
+            JournalKind::VoidRefund => "void_refund",
+            JournalKind::Uncalled => "uncalled",
+            JournalKind::Issue => "issue",
+            JournalKind::Retire => "retire",
+            JournalKind::Transfer => "transfer",
+            JournalKind::WriteOff => "write_off",
+            JournalKind::Adjustment => "adjustment",
+            JournalKind::EntryFee => "entry_fee",
+            JournalKind::Prize => "prize",
+        }
+    }
+
+    /// Movements that bring chips onto a table or take them off it, as opposed to moves between
+    /// a table's own stacks and pot.
+    pub fn crosses_table(&self) -> bool {
+        !matches!(
+            self,
+            JournalKind::Blind
+                | JournalKind::Ante
+                | JournalKind::Bet
+                | JournalKind::PotAward
+                | JournalKind::VoidRefund
+                | JournalKind::Uncalled
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Posting {
+    pub account: String,
+    pub amount: i64,
+}
+
+/// One balanced chip movement: its postings always sum to zero.
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    pub table_id: String,
+    pub hand_id: Option<String>,
+    pub kind: JournalKind,
+    pub at: DateTime<Utc>,
+    pub postings: Vec<Posting>,
+    /// Who made an operator's correction, and why.
+    pub memo: Option<String>,
+}
+
+impl JournalEntry {
+    pub fn transfer(
+        table_id: &str,
+        hand_id: Option<String>,
+        kind: JournalKind,
+        from: LedgerAccount,
+        to: LedgerAccount,
+        amount: u64,
+    ) -> Self {
+        let amount = amount as i64;
+        Self {
+            table_id: table_id.to_string(),
+            hand_id,
+            kind,
+            at: Utc::now(),
+            postings: vec![
+                Posting { account: from.to_string(), amount: -amount },
+                Posting { account: to.to_string(), amount },
+            ],
+            memo: None,
+        }
+    }
+
+    pub fn is_balanced(&self) -> bool {
+        self.postings.iter().map(|p| p.amount).sum::<i64>() == 0
+    }
+}
+
+/// What a table sends to the ledger, in the order its chips moved.
+#[derive(Debug)]
+pub enum LedgerRecord {
+    Journal(JournalEntry),
+    CashOut(CashOut),
+    /// A sit-and-go entry fee handed back to a player who left before the start.
+    Refund(CashOut),
+    /// A finishing position's share of the prize pool.
+    Prize(CashOut),
+    /// The table's chips at this point in its stream of records, to check against the ledger.
+    Checkpoint { table_id: String, stacks: u64, pot: u64, reply: oneshot::Sender<TableReconciliation> },
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TableReconciliation {
+    pub table_id: String,
+    pub stacks: u64,
+    pub pot: u64,
+    /// Chips brought onto the table less chips taken off it, rake aside.
+    pub net_buy_ins: i64,
+    pub rake: i64,
+    pub discrepancy: i64,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ReconciliationReport {
+    pub checked_at: DateTime<Utc>,
+    pub tables: Vec<TableReconciliation>,
+    pub unreachable_tables: Vec<String>,
+    pub unbalanced_journals: Vec<i64>,
+}
+
+impl ReconciliationReport {
+    pub fn is_clean(&self) -> bool {
+        self.unreachable_tables.is_empty()
+            && self.unbalanced_journals.is_empty()
+            && self.tables.iter().all(|t| t.discrepancy == 0)
+    }
+}
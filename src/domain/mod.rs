@@ -3,9 +3,25 @@ pub mod game;
 pub mod web_socket;
 pub mod card_store;
 pub mod player;
+pub mod variant;
+pub mod table;
+pub mod tournament;
+pub mod history;
+pub mod game_log;
+pub mod account;
+pub mod ledger;
+pub mod fairness;
 
 pub use cards::*;
 pub use game::*;
 pub use web_socket::*;
 pub use player::*;
+pub use variant::*;
+pub use table::*;
+pub use tournament::*;
+pub use history::*;
+pub use game_log::*;
+pub use account::*;
+pub use ledger::*;
+pub use fairness::*;
 pub use card_store::CardStore;
@@ -1,42 +1,36 @@
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
 
-use crate::{domain::Card};
-use crate::shared::INITIAL_HAND_SIZE;
+use crate::domain::Card;
 
-#[derive(Clone, Serialize, Deserialize)]
-pub struct Player {
-    pub id: String,
-    pub seat: usize,
-    pub stack: u64,
-    pub hand: Option<[Card; 2]>,
-    pub status: PlayerStatus,
-    pub committed: u64,
-}
-
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PublicPlayer {
     pub id: String,
     pub seat: usize,
     pub stack: u64,
     pub status: PlayerStatus,
     pub committed: u64,
+    pub cards_drawn: Vec<usize>,
 }
 
-#[derive(Serialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct RevealedHand {
     pub seat: usize,
-    pub hand: [Card; INITIAL_HAND_SIZE],
+    pub board: usize,
+    pub hand: Vec<Card>,
+    pub description: String,
 }
 
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 pub struct PrivateState {
-    pub hand: Option<[Card; INITIAL_HAND_SIZE]>
+    pub hand: Option<Vec<Card>>
 }
 
-#[derive(Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 pub enum PlayerStatus {
     Waiting,
     Active,
     Folded,
     AllIn,
+    SittingOut,
 }
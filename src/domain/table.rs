@@ -0,0 +1,119 @@
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+
+use crate::domain::{BettingStructure, GameVariant, PublicGameState, TournamentConfig, VariantSpec};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TableConfig {
+    pub sequence: Vec<VariantSpec>,
+    #[serde(default)]
+    pub rotation: RotationRule,
+    /// Most boards players may vote to run once everyone is all in; the default of 1 runs one without a vote.
+    #[serde(default = "default_max_boards")]
+    pub max_boards: u8,
+    #[serde(default)]
+    pub sit_and_go: Option<TournamentConfig>,
+    #[serde(default)]
+    pub rake: Option<RakeConfig>,
+}
+
+fn default_max_boards() -> u8 {
+    1
+}
+
+/// What the house takes from each cash game pot.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RakeConfig {
+    /// Share of the pot taken, in hundredths of a percent: 500 takes 5%.
+    pub basis_points: u32,
+    /// The most taken from one hand, by how many players were dealt in. Empty means no cap.
+    #[serde(default)]
+    pub caps: Vec<RakeCap>,
+    /// Take nothing from hands that end before the flop, or before the first draw.
+    #[serde(default = "default_no_flop_no_drop")]
+    pub no_flop_no_drop: bool,
+}
+
+fn default_no_flop_no_drop() -> bool {
+    true
+}
+
+/// The cap for hands dealt to `players` or more, up to the next entry. The first entry also
+/// covers hands dealt to fewer players.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+pub struct RakeCap {
+    pub players: usize,
+    pub cap: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum RotationRule {
+    #[default]
+    EveryOrbit,
+    EveryHands { hands: u32 },
+}
+
+impl TableConfig {
+    pub fn single(variant: GameVariant) -> Self {
+        Self {
+            sequence: vec![VariantSpec::new(variant)],
+            rotation: RotationRule::default(),
+            max_boards: default_max_boards(),
+            sit_and_go: None,
+            rake: None,
+        }
+    }
+
+    pub fn is_mixed(&self) -> bool {
+        self.sequence.len() > 1
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.sequence.is_empty() {
+            return Err("a table needs at least one game".into());
+        }
+        if self.sequence.iter().any(|spec| spec.small_blind == 0 || spec.big_blind < spec.small_blind) {
+            return Err("blinds must be positive and the big blind at least the small blind".into());
+        }
+        if self.max_boards == 0 {
+            return Err("max_boards must be at least 1".into());
+        }
+        if let Some(rake) = &self.rake {
+            if self.sit_and_go.is_some() {
+                return Err("rake applies only to cash tables".into());
+            }
+            if rake.basis_points > 10_000 {
+                return Err("rake cannot be more than 100% of the pot".into());
+            }
+            if rake.caps.windows(2).any(|pair| pair[0].players >= pair[1].players) {
+                return Err("rake caps must be in increasing order of players".into());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A cash table as listed in the lobby.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct TableSummary {
+    pub table_id: String,
+    pub variant: GameVariant,
+    pub betting_structure: BettingStructure,
+    pub small_blind: u64,
+    pub big_blind: u64,
+    pub seated: usize,
+    pub max_players: usize,
+    pub mixed: bool,
+    pub sit_and_go: bool,
+    pub hand_in_progress: bool,
+}
+
+/// A table's configuration together with what is on it right now.
+#[derive(Clone, Serialize, JsonSchema)]
+pub struct TableDetails {
+    #[serde(flatten)]
+    pub summary: TableSummary,
+    pub config: TableConfig,
+    pub state: PublicGameState,
+}
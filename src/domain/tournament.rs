@@ -0,0 +1,216 @@
+use std::path::Path;
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+use tokio::sync::oneshot;
+
+use crate::domain::{GameVariant, TableHandle};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TournamentConfig {
+    pub entrants: usize,
+    pub buy_in: u64,
+    pub starting_stack: u64,
+    #[serde(default)]
+    pub schedule: BlindSchedule,
+    /// TOML or JSON file with a reusable structure; replaces `schedule` when set.
+    #[serde(default)]
+    pub schedule_file: Option<String>,
+    /// Percentage of the prize pool paid to each finishing position, starting with first place.
+    pub payouts: Vec<u64>,
+    /// Last level (one-based) at which players may still register, re-enter or rebuy.
+    #[serde(default)]
+    pub late_registration_level: Option<usize>,
+    #[serde(default)]
+    pub reentry: bool,
+    /// Rebuys cost one buy-in for one starting stack.
+    #[serde(default)]
+    pub rebuys: bool,
+    /// Offered once per player at the first break.
+    #[serde(default)]
+    pub add_on: Option<AddOn>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct AddOn {
+    pub cost: u64,
+    pub chips: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Purchase {
+    Rebuy,
+    AddOn,
+    ReEntry,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MultiTableConfig {
+    pub table_size: usize,
+    #[serde(default)]
+    pub variant: GameVariant,
+    pub tournament: TournamentConfig,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct BlindLevel {
+    pub small_blind: u64,
+    pub big_blind: u64,
+    #[serde(default)]
+    pub ante: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, JsonSchema)]
+pub struct BlindSchedule {
+    pub levels: Vec<ScheduleStep>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleStep {
+    Level {
+        small_blind: u64,
+        big_blind: u64,
+        #[serde(default)]
+        ante: u64,
+        duration: LevelDuration,
+    },
+    Break { minutes: u64 },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LevelDuration {
+    Minutes(u64),
+    Hands(u32),
+}
+
+impl BlindSchedule {
+    pub fn from_file(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let raw = std::fs::read_to_string(path)?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("toml") => Ok(toml::from_str(&raw)?),
+            _ => Ok(serde_json::from_str(&raw)?),
+        }
+    }
+}
+
+impl TournamentConfig {
+    /// What a purchase costs from the bankroll: a buy-in, or the add-on's own cost.
+    pub fn price(&self, purchase: Purchase) -> u64 {
+        match purchase {
+            Purchase::Rebuy | Purchase::ReEntry => self.buy_in,
+            Purchase::AddOn => self.add_on.as_ref().map_or(0, |add_on| add_on.cost),
+        }
+    }
+
+    pub fn load_schedule_file(&mut self) -> anyhow::Result<()> {
+        if let Some(path) = &self.schedule_file {
+            self.schedule = BlindSchedule::from_file(path)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct TournamentResult {
+    pub player_id: String,
+    pub position: usize,
+    pub prize: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "type", content = "data")]
+pub enum TournamentEvent {
+    PlayerSeated { player_id: String, table_id: usize },
+    PlayerEliminated { player_id: String, position: usize },
+    LevelChanged { level: usize, blinds: BlindLevel },
+    Clock(ClockStatus),
+    PrizePoolChanged(PrizePool),
+    DealProposed(DealProposal),
+    DealAccepted { player_id: String },
+    DealRejected { player_id: String },
+    HandForHand { active: bool },
+    TableBroken { table_id: usize },
+    FinalTable { table_id: usize },
+    Finished { results: Vec<TournamentResult> },
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ClockStatus {
+    pub level: usize,
+    pub on_break: bool,
+    pub seconds_remaining: Option<u64>,
+    pub hands_remaining: Option<u32>,
+    pub next_level: Option<BlindLevel>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum DealMethod {
+    Icm,
+    ChipChop,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action")]
+pub enum DealAction {
+    #[serde(rename = "propose_deal")]
+    Propose { method: DealMethod },
+    #[serde(rename = "accept_deal")]
+    Accept,
+    #[serde(rename = "reject_deal")]
+    Reject,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DealShare {
+    pub player_id: String,
+    pub stack: u64,
+    pub icm: u64,
+    pub chip_chop: u64,
+    /// What the player takes under the proposed method.
+    pub amount: u64,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct DealProposal {
+    pub proposer: String,
+    pub method: DealMethod,
+    pub shares: Vec<DealShare>,
+    pub accepted: Vec<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
+pub struct PrizePool {
+    /// Every paid entry, re-entries included.
+    pub entries: usize,
+    pub rebuys: usize,
+    pub add_ons: usize,
+    pub prize_pool: u64,
+}
+
+/// Sent by a director-managed table after every hand; the table then waits for `StartHand`.
+pub struct HandReport {
+    pub table_id: usize,
+    /// Busted players with the chips they started the hand with.
+    pub busted: Vec<(String, u64)>,
+    pub seated: Vec<String>,
+}
+
+pub struct ReleasedPlayer {
+    pub player_id: String,
+    pub stack: u64,
+    pub away: bool,
+}
+
+pub enum DirectorCommand {
+    Register { player_id: String, reply: oneshot::Sender<Result<String, String>> },
+    Locate { player_id: String, reply: oneshot::Sender<Option<TableHandle>> },
+    Purchase { player_id: String, purchase: Purchase, reply: oneshot::Sender<Result<(), String>> },
+    Deal { player_id: String, action: DealAction, reply: oneshot::Sender<Result<(), String>> },
+    /// Drains every tournament table; the reply comes once all of them have.
+    Drain { reply: oneshot::Sender<()> },
+}
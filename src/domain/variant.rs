@@ -0,0 +1,61 @@
+use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum GameVariant {
+    #[default]
+    Holdem,
+    FiveCardDraw,
+    TripleDraw27,
+}
+
+impl GameVariant {
+    pub fn hole_cards(self) -> usize {
+        match self {
+            GameVariant::Holdem => 2,
+            GameVariant::FiveCardDraw | GameVariant::TripleDraw27 => 5,
+        }
+    }
+
+    pub fn draw_rounds(self) -> u8 {
+        match self {
+            GameVariant::Holdem => 0,
+            GameVariant::FiveCardDraw => 1,
+            GameVariant::TripleDraw27 => 3,
+        }
+    }
+
+    pub fn uses_board(self) -> bool {
+        matches!(self, GameVariant::Holdem)
+    }
+
+    pub fn is_lowball(self) -> bool {
+        matches!(self, GameVariant::TripleDraw27)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[allow(clippy::enum_variant_names)]
+pub enum BettingStructure {
+    #[default]
+    NoLimit,
+    PotLimit,
+    FixedLimit,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+pub struct VariantSpec {
+    pub variant: GameVariant,
+    #[serde(default)]
+    pub structure: BettingStructure,
+    pub small_blind: u64,
+    pub big_blind: u64,
+}
+
+impl VariantSpec {
+    pub fn new(variant: GameVariant) -> Self {
+        Self { variant, structure: BettingStructure::default(), small_blind: 10, big_blind: 20 }
+    }
+}
@@ -1,33 +1,110 @@
 use serde::{Serialize, Deserialize};
+use schemars::JsonSchema;
 
-use crate::domain::{Card, PrivateState, PublicGameState};
+use crate::domain::{
+    DealAction, DealMethod, PotAward, PrivateState, PublicGameState, Purchase, RevealedHand, TournamentEvent, VariantSpec,
+};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "type", content = "data")]
-pub enum WebSocketEvent {
-    Deal { cards: Vec<Card> },
-    Shuffle,
-    Error { message: String },
-}
-
-#[derive(Clone, Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "data")]
 pub enum ServerEvent {
     RoundStarted,
     GameState(PublicGameState),
     PrivateState(PrivateState),
     BlindPosted { seat: usize, amount: u64 },
+    AntePosted { seat: usize, amount: u64 },
+    Showdown { hands: Vec<RevealedHand>, awards: Vec<PotAward>, rake: u64 },
+    VariantChanged(VariantSpec),
+    Tournament(TournamentEvent),
+    /// The server seed behind a finished hand's shuffle and the commitment it opens. The full proof
+    /// gives away every hole card, so only players dealt in can fetch it, from `/hands/{hand_id}/shuffle`.
+    ShuffleRevealed { hand_number: u64, hand_id: Option<String>, commitment: String, server_seed: String },
+    /// The server is going down once the hand in progress is over; no new hands will be dealt.
+    ServerShuttingDown,
+    /// An operator paused the table; no actions are taken until it resumes.
+    TablePaused,
+    TableResumed,
+    /// The hand was called off and every chip committed to it given back.
+    HandVoided { hand_number: u64 },
+    /// An operator took the player's seat. The player's own connection closes after this.
+    PlayerRemoved { player_id: String, banned: bool },
+    Announcement { message: String },
+    /// An operator closed the table and cashed every player out.
+    TableClosed,
     Error { message: String },
 }
 
-#[derive(Serialize, Deserialize)]
-#[serde(tag = "type", content = "data")]
+/// A message from a client, tagged by `action`. Unknown fields are ignored.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "action", rename_all = "snake_case")]
 pub enum ClientEvent {
-    Join { player_id: String },
-    Action { action: PlayerAction },
+    Fold,
+    Check,
+    Call,
+    Bet {
+        #[serde(default)]
+        amount: u64,
+    },
+    Raise {
+        #[serde(default)]
+        amount: u64,
+    },
+    #[serde(rename = "allin")]
+    AllIn,
+    /// Positions in the hand of the cards to throw away.
+    Draw {
+        #[serde(default)]
+        cards: Vec<usize>,
+    },
+    RunBoards {
+        #[serde(default = "one_board")]
+        boards: u8,
+    },
+    Rebuy,
+    AddOn,
+    ReEntry,
+    ProposeDeal { method: DealMethod },
+    AcceptDeal,
+    RejectDeal,
+    /// Mixed into the shuffle of every hand dealt to this player from now on.
+    ClientSeed { seed: String },
+}
+
+fn one_board() -> u8 {
+    1
+}
+
+/// What a client message asks for: a move at the table, a shuffle seed or a tournament request.
+pub enum ClientRequest {
+    Action(PlayerAction),
+    Purchase(Purchase),
+    Deal(DealAction),
+    ClientSeed(String),
+}
+
+impl From<ClientEvent> for ClientRequest {
+    fn from(event: ClientEvent) -> Self {
+        match event {
+            ClientEvent::Fold => Self::Action(PlayerAction::Fold),
+            ClientEvent::Check => Self::Action(PlayerAction::Check),
+            ClientEvent::Call => Self::Action(PlayerAction::Call),
+            ClientEvent::Bet { amount } => Self::Action(PlayerAction::Bet { amount }),
+            ClientEvent::Raise { amount } => Self::Action(PlayerAction::Raise { amount }),
+            ClientEvent::AllIn => Self::Action(PlayerAction::AllIn),
+            ClientEvent::Draw { cards } => Self::Action(PlayerAction::Draw { discard: cards }),
+            ClientEvent::RunBoards { boards } => Self::Action(PlayerAction::RunBoards { boards }),
+            ClientEvent::Rebuy => Self::Purchase(Purchase::Rebuy),
+            ClientEvent::AddOn => Self::Purchase(Purchase::AddOn),
+            ClientEvent::ReEntry => Self::Purchase(Purchase::ReEntry),
+            ClientEvent::ProposeDeal { method } => Self::Deal(DealAction::Propose { method }),
+            ClientEvent::AcceptDeal => Self::Deal(DealAction::Accept),
+            ClientEvent::RejectDeal => Self::Deal(DealAction::Reject),
+            ClientEvent::ClientSeed { seed } => Self::ClientSeed(seed),
+        }
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum PlayerAction {
     Fold,
     Check,
@@ -35,4 +112,6 @@ pub enum PlayerAction {
     Bet { amount: u64 },
     Raise { amount: u64 },
     AllIn,
+    Draw { discard: Vec<usize> },
+    RunBoards { boards: u8 },
 }
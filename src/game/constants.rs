@@ -1,3 +1,13 @@
 pub(crate) const COMMAND_CHANNEL_CAPACITY: usize = 256;
 pub(crate) const FLOP_CARDS: usize = 3;
-pub(crate) const MAX_PLAYERS: usize = 9;
\ No newline at end of file
+pub(crate) const BOARD_CARDS: usize = 5;
+pub(crate) const MAX_PLAYERS: usize = 9;
+pub(crate) const BASIS_POINTS: u64 = 10_000;
+pub(crate) const FIXED_LIMIT_BET_CAP: u32 = 4;
+pub(crate) const CLOCK_TICK_SECS: u64 = 1;
+pub(crate) const CLOCK_BROADCAST_TICKS: u64 = 10;
+pub(crate) const SNAPSHOT_INTERVAL_SECS: u64 = 30;
+pub(crate) const RESEED_INTERVAL_SECS: u64 = 60;
+pub(crate) const MAX_TABLES: usize = 50;
+pub(crate) const MAX_CLIENT_SEED_LEN: usize = 64;
+pub(crate) const TOURNAMENT_LEDGER_ID: &str = "tournament";
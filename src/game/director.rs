@@ -0,0 +1,602 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, broadcast, oneshot};
+use rand::{rngs::StdRng, SeedableRng};
+use rand::seq::SliceRandom;
+
+use crate::domain::{
+    CashOut, DealAction, DirectorCommand, GameCommand, HandReport, LedgerRecord, MultiTableConfig,
+    PlayerStatus, Purchase, ReleasedPlayer, TableConfig, TableHandle, TournamentEvent
+};
+
+use crate::game::{GameManager, TableRecorder};
+use crate::game::constants::{
+    CLOCK_BROADCAST_TICKS, CLOCK_TICK_SECS, COMMAND_CHANNEL_CAPACITY, MAX_PLAYERS, TOURNAMENT_LEDGER_ID,
+};
+use crate::game::tournament::Tournament;
+use crate::infrastructure::AccountStore;
+
+struct TableEntry {
+    handle: TableHandle,
+    players: HashSet<String>,
+    /// Between hands and waiting for the director to deal the next one.
+    waiting: bool,
+}
+
+/// Runs a multi-table tournament. Nothing here is persisted: a restart loses the tournament.
+pub struct TournamentDirector {
+    config: MultiTableConfig,
+    tournament: Tournament,
+    tables: BTreeMap<usize, TableEntry>,
+    next_table_id: usize,
+    locations: HashMap<String, usize>,
+    hand_for_hand: bool,
+    /// Tables that finished a hand since the schedule last counted one.
+    reported: HashSet<usize>,
+    clock_ticks: u64,
+    events: broadcast::Sender<TournamentEvent>,
+    reports_tx: mpsc::UnboundedSender<HandReport>,
+    recorder: TableRecorder,
+    /// Entry fees are taken from bankrolls here; prizes go back through the ledger writer.
+    accounts: AccountStore,
+    rng: StdRng,
+    draining: bool,
+}
+
+impl TournamentDirector {
+    pub fn start(
+        config: MultiTableConfig,
+        recorder: TableRecorder,
+        accounts: AccountStore,
+    ) -> Result<(mpsc::Sender<DirectorCommand>, broadcast::Sender<TournamentEvent>), String> {
+        if !(2..=MAX_PLAYERS).contains(&config.table_size) {
+            return Err(format!("table_size must be between 2 and {MAX_PLAYERS}"));
+        }
+        let (tx_cmd, rx_cmd) = mpsc::channel::<DirectorCommand>(COMMAND_CHANNEL_CAPACITY);
+        let (reports_tx, reports_rx) = mpsc::unbounded_channel();
+        let (events, _) = broadcast::channel(128);
+
+        let mut director = TournamentDirector {
+            tournament: Tournament::new(config.tournament.clone()),
+            config,
+            tables: BTreeMap::new(),
+            next_table_id: 0,
+            locations: HashMap::new(),
+            hand_for_hand: false,
+            reported: HashSet::new(),
+            clock_ticks: 0,
+            events: events.clone(),
+            reports_tx,
+            recorder,
+            accounts,
+            rng: StdRng::from_entropy(),
+            draining: false,
+        };
+
+        tokio::spawn(async move { director.run(rx_cmd, reports_rx).await; });
+
+        Ok((tx_cmd, events))
+    }
+
+    async fn run(
+        &mut self,
+        mut commands: mpsc::Receiver<DirectorCommand>,
+        mut reports: mpsc::UnboundedReceiver<HandReport>,
+    ) {
+        let mut clock = tokio::time::interval(Duration::from_secs(CLOCK_TICK_SECS));
+
+        loop {
+            tokio::select! {
+                cmd = commands.recv() => match cmd {
+                    // An entrant registering again is a socket coming back, not a new entry.
+                    Some(DirectorCommand::Register { player_id, reply }) if self.tournament.entrants.contains(&player_id) => {
+                        let _ = reply.send(Ok(player_id));
+                    }
+                    Some(DirectorCommand::Register { player_id, reply }) => {
+                        let res = self.register(player_id).await;
+                        let registered = res.clone().ok();
+                        let _ = reply.send(res);
+
+                        match registered {
+                            Some(_) if self.tournament.ready_to_start() => self.start_tables().await,
+                            Some(player_id) if self.tournament.is_running() => self.seat_late(player_id).await,
+                            _ => {}
+                        }
+                    }
+                    Some(DirectorCommand::Purchase { player_id, purchase, reply }) => {
+                        let res = self.purchase(&player_id, purchase).await;
+                        let _ = reply.send(res);
+                    }
+                    Some(DirectorCommand::Deal { player_id, action, reply }) => {
+                        let res = self.handle_deal(&player_id, action).await;
+                        let _ = reply.send(res);
+                    }
+                    Some(DirectorCommand::Locate { player_id, reply }) => {
+                        let _ = reply.send(self.locate(&player_id));
+                    }
+                    Some(DirectorCommand::Drain { reply }) => self.drain(reply).await,
+                    None => break,
+                },
+                Some(report) = reports.recv() => self.handle_report(report).await,
+                _ = clock.tick() => self.on_clock_tick().await,
+            }
+        }
+        tracing::info!("TournamentDirector actor exiting (command channel closed)");
+    }
+
+    /// Takes the entry fee first and hands it back if the tournament turns the player away.
+    async fn register(&mut self, player_id: String) -> Result<String, String> {
+        if self.draining {
+            return Err("the server is shutting down".into());
+        }
+        let fee = self.tournament.config.buy_in;
+        self.accounts.enter_tournament(&player_id, TOURNAMENT_LEDGER_ID, fee).await?;
+        if let Err(e) = self.tournament.register(player_id.clone()) {
+            self.refund(&player_id, fee).await;
+            return Err(e);
+        }
+        let _ = self.events.send(TournamentEvent::PrizePoolChanged(self.tournament.prize_pool_status()));
+        Ok(player_id)
+    }
+
+    /// Charged like an entry: paid before the chips are granted and refunded if they are not.
+    async fn purchase(&mut self, player_id: &str, purchase: Purchase) -> Result<(), String> {
+        if self.draining {
+            return Err("the server is shutting down".into());
+        }
+        let table = self.locate(player_id);
+
+        let price = self.tournament.config.price(purchase);
+        self.accounts.enter_tournament(player_id, TOURNAMENT_LEDGER_ID, price).await?;
+        let chips = match purchase {
+            Purchase::ReEntry => self.tournament.reenter(player_id),
+            Purchase::AddOn => self.tournament.add_on(player_id),
+            Purchase::Rebuy => {
+                let stack = match &table {
+                    Some(table) => Self::stack_at(table, player_id).await,
+                    None => None,
+                };
+                match stack {
+                    Some(0) => Err("busted players re-enter instead".into()),
+                    Some(stack) => self.tournament.rebuy(player_id, stack),
+                    None => Err("not seated".into()),
+                }
+            }
+        };
+        let chips = match chips {
+            Ok(chips) => chips,
+            Err(e) => {
+                self.refund(player_id, price).await;
+                return Err(e);
+            }
+        };
+        let _ = self.events.send(TournamentEvent::PrizePoolChanged(self.tournament.prize_pool_status()));
+
+        match (purchase, table) {
+            (Purchase::ReEntry, _) => self.seat_late(player_id.to_string()).await,
+            (_, Some(table)) => {
+                let cmd = GameCommand::AddChips { player_id: player_id.to_string(), amount: chips };
+                let _ = table.commands.send(cmd).await;
+            }
+            (_, None) => {}
+        }
+        Ok(())
+    }
+
+    async fn refund(&self, player_id: &str, fee: u64) {
+        if let Err(e) = self.accounts.refund_entry(player_id, TOURNAMENT_LEDGER_ID, fee).await {
+            tracing::error!(%player_id, fee, "could not refund entry: {e}");
+        }
+    }
+
+    fn finish(&mut self) {
+        let results = self.tournament.finish();
+        for result in results.iter().filter(|r| r.prize > 0) {
+            let prize = CashOut { account_id: result.player_id.clone(), table_id: TOURNAMENT_LEDGER_ID.into(), amount: result.prize, bought: 0 };
+            let _ = self.recorder.ledger.send(LedgerRecord::Prize(prize));
+        }
+        let _ = self.events.send(TournamentEvent::Finished { results });
+        self.tables.clear();
+    }
+
+    async fn handle_deal(&mut self, player_id: &str, action: DealAction) -> Result<(), String> {
+        match action {
+            DealAction::Propose { method } => {
+                if self.tables.len() > 1 {
+                    return Err("deals are only made at the final table".into());
+                }
+                self.tournament.request_deal(player_id, method)?;
+                self.open_deal().await;
+            }
+            DealAction::Accept => {
+                let agreed = self.tournament.accept_deal(player_id)?;
+                let _ = self.events.send(TournamentEvent::DealAccepted { player_id: player_id.to_string() });
+                if agreed {
+                    self.finish();
+                }
+            }
+            DealAction::Reject => {
+                self.tournament.reject_deal(player_id)?;
+                let _ = self.events.send(TournamentEvent::DealRejected { player_id: player_id.to_string() });
+                self.deal_next_hands().await;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prices a requested deal once the final table is between hands.
+    async fn open_deal(&mut self) {
+        let Some(entry) = self.tables.values().next().filter(|t| t.waiting) else { return; };
+
+        let (tx, rx) = oneshot::channel();
+        if entry.handle.commands.send(GameCommand::GetState { reply: tx }).await.is_err() {
+            return;
+        }
+        let Ok(state) = rx.await else { return; };
+
+        let stacks: Vec<(String, u64)> = state.players
+            .iter()
+            .filter(|p| p.status != PlayerStatus::SittingOut && p.stack > 0)
+            .map(|p| (p.id.clone(), p.stack))
+            .collect();
+        if let Some(proposal) = self.tournament.open_deal(&stacks) {
+            let _ = self.events.send(TournamentEvent::DealProposed(proposal));
+        }
+    }
+
+    /// Counts chips already bought for the next hand, so rebuys cannot be stacked up past the
+    /// starting stack.
+    async fn stack_at(table: &TableHandle, player_id: &str) -> Option<u64> {
+        let (tx, rx) = oneshot::channel();
+        let cmd = GameCommand::PlayerChips { player_id: player_id.to_string(), reply: tx };
+        table.commands.send(cmd).await.ok()?;
+        rx.await.ok().flatten()
+    }
+
+    /// Late registrations and re-entries join the shortest table, or a new one when all are full.
+    async fn seat_late(&mut self, player_id: String) {
+        let open_table = self.tables
+            .iter()
+            .filter(|(_, t)| t.players.len() < self.config.table_size)
+            .min_by_key(|(_, t)| t.players.len())
+            .map(|(id, _)| *id);
+        let table_id = match open_table {
+            Some(table_id) => table_id,
+            None => self.open_table().await,
+        };
+
+        let stack = self.tournament.config.starting_stack;
+        self.seat_or_open(table_id, player_id, stack).await;
+        self.deal_next_hands().await;
+    }
+
+    async fn open_table(&mut self) -> usize {
+        let table_id = self.next_table_id;
+        self.next_table_id += 1;
+
+        let config = TableConfig::single(self.config.variant);
+        let handle = GameManager::start_managed(config, table_id, self.reports_tx.clone(), self.recorder.clone());
+        if let Some(blinds) = self.tournament.current_blinds() {
+            let _ = handle.commands.send(GameCommand::SetBlinds { blinds }).await;
+        }
+
+        self.tables.insert(table_id, TableEntry { handle, players: HashSet::new(), waiting: true });
+        table_id
+    }
+
+    /// Tables finish their hands on their own; the reply waits for all of them off the director's
+    /// loop.
+    async fn drain(&mut self, reply: oneshot::Sender<()>) {
+        self.draining = true;
+        let mut drained = vec![];
+        for entry in self.tables.values() {
+            let (tx, rx) = oneshot::channel();
+            if entry.handle.commands.send(GameCommand::Drain { reply: tx }).await.is_ok() {
+                drained.push(rx);
+            }
+        }
+        tokio::spawn(async move {
+            for rx in drained {
+                let _ = rx.await;
+            }
+            let _ = reply.send(());
+        });
+    }
+
+    fn locate(&self, player_id: &str) -> Option<TableHandle> {
+        let table_id = self.locations.get(player_id)?;
+        self.tables.get(table_id).map(|t| t.handle.clone())
+    }
+
+    async fn start_tables(&mut self) {
+        let mut players = self.tournament.entrants.clone();
+        players.shuffle(&mut self.rng);
+
+        let table_count = players.len().div_ceil(self.config.table_size.max(2));
+        self.tournament.start(Instant::now());
+
+        let mut table_ids = vec![];
+        for _ in 0..table_count {
+            table_ids.push(self.open_table().await);
+        }
+
+        let stack = self.tournament.config.starting_stack;
+        for (i, player_id) in players.into_iter().enumerate() {
+            self.seat_or_open(table_ids[i % table_count], player_id, stack).await;
+        }
+
+        self.update_level().await;
+        self.deal_next_hands().await;
+    }
+
+    async fn handle_report(&mut self, report: HandReport) {
+        let Some(entry) = self.tables.get_mut(&report.table_id) else { return; };
+        entry.players = report.seated.into_iter().collect();
+        entry.waiting = true;
+        self.count_hand(report.table_id);
+
+        for (player_id, _) in &report.busted {
+            self.locations.remove(player_id);
+        }
+        for (player_id, position) in self.tournament.eliminate(report.busted) {
+            let _ = self.events.send(TournamentEvent::PlayerEliminated { player_id, position });
+        }
+
+        if self.tournament.remaining() <= 1 {
+            self.finish();
+            return;
+        }
+
+        // Tables stop taking seats once they drain, so a player moved now would have nowhere to go.
+        if !self.draining {
+            self.rebalance(report.table_id).await;
+        }
+        self.update_level().await;
+        self.update_hand_for_hand();
+        self.open_deal().await;
+        self.deal_next_hands().await;
+    }
+
+    /// Runs while `table_id` is between hands, so its players can be moved safely.
+    async fn rebalance(&mut self, table_id: usize) {
+        let count = |tables: &BTreeMap<usize, TableEntry>, id: usize| tables[&id].players.len();
+
+        let smallest = self.tables.values().map(|t| t.players.len()).min().unwrap_or(0);
+        let capacity_without_one = (self.tables.len() - 1) * self.config.table_size;
+        if self.tables.len() > 1
+            && self.tournament.remaining() <= capacity_without_one
+            && count(&self.tables, table_id) == smallest
+        {
+            self.break_table(table_id).await;
+            return;
+        }
+
+        while let Some(target) = self.smallest_table(table_id) {
+            if count(&self.tables, table_id) < count(&self.tables, target) + 2 {
+                break;
+            }
+            if !self.move_player(table_id, target).await {
+                break;
+            }
+        }
+    }
+
+    /// A table whose players cannot all be moved stays up and is tried again after its next hand.
+    async fn break_table(&mut self, table_id: usize) {
+        while !self.tables[&table_id].players.is_empty() {
+            let Some(target) = self.smallest_table(table_id) else { return; };
+            if !self.move_player(table_id, target).await {
+                return;
+            }
+        }
+
+        self.tables.remove(&table_id);
+        let _ = self.events.send(TournamentEvent::TableBroken { table_id });
+
+        if self.tables.len() == 1 {
+            if let Some(&final_table) = self.tables.keys().next() {
+                let _ = self.events.send(TournamentEvent::FinalTable { table_id: final_table });
+            }
+        }
+    }
+
+    fn smallest_table(&self, excluding: usize) -> Option<usize> {
+        self.tables
+            .iter()
+            .filter(|(id, _)| **id != excluding)
+            .min_by_key(|(_, t)| t.players.len())
+            .map(|(id, _)| *id)
+    }
+
+    /// Moves the player due for the big blind at `from` into the seat at `to` that posts next.
+    async fn move_player(&mut self, from: usize, to: usize) -> bool {
+        let (tx, rx) = oneshot::channel();
+        let source = &self.tables[&from].handle.commands;
+        if source.send(GameCommand::ReleasePlayer { reply: tx }).await.is_err() {
+            return false;
+        }
+
+        let released = match rx.await {
+            Ok(Ok(released)) => released,
+            Ok(Err(e)) => {
+                tracing::warn!(table_id = from, "could not release player: {e}");
+                return false;
+            }
+            Err(_) => return false,
+        };
+
+        if let Some(entry) = self.tables.get_mut(&from) {
+            entry.players.remove(&released.player_id);
+        }
+        let ReleasedPlayer { player_id, stack, away } = released;
+        if self.seat(to, player_id.clone(), stack, away).await {
+            return true;
+        }
+        if !self.seat(from, player_id.clone(), stack, away).await {
+            tracing::error!(%player_id, table_id = from, "could not seat a moved player back");
+        }
+        false
+    }
+
+    /// Seats a new entrant at `table_id`, or at a fresh table if that one turns them away.
+    async fn seat_or_open(&mut self, table_id: usize, player_id: String, stack: u64) {
+        if self.seat(table_id, player_id.clone(), stack, false).await {
+            return;
+        }
+        let table_id = self.open_table().await;
+        if !self.seat(table_id, player_id.clone(), stack, false).await {
+            tracing::error!(%player_id, "could not seat player");
+        }
+    }
+
+    async fn seat(&mut self, table_id: usize, player_id: String, stack: u64, away: bool) -> bool {
+        let Some(entry) = self.tables.get_mut(&table_id) else { return false; };
+
+        let (tx, rx) = oneshot::channel();
+        let cmd = GameCommand::SeatPlayer { player_id: player_id.clone(), stack, away, reply: tx };
+        if entry.handle.commands.send(cmd).await.is_err() {
+            return false;
+        }
+
+        match rx.await {
+            Ok(Ok(_)) => {
+                entry.players.insert(player_id.clone());
+                self.locations.insert(player_id.clone(), table_id);
+                let _ = self.events.send(TournamentEvent::PlayerSeated { player_id, table_id });
+                true
+            }
+            Ok(Err(e)) => {
+                tracing::warn!(table_id, "could not seat player: {e}");
+                false
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Hand-based levels count a hand once every table has played one.
+    fn count_hand(&mut self, table_id: usize) {
+        self.reported.insert(table_id);
+        if !self.tables.keys().all(|id| self.reported.contains(id)) { return; }
+
+        self.reported.clear();
+        if let Some(clock) = self.tournament.clock_mut() {
+            clock.record_hand();
+        }
+    }
+
+    async fn on_clock_tick(&mut self) {
+        if !self.tournament.is_running() { return; }
+        let Some(clock) = self.tournament.clock_mut() else { return; };
+
+        let now = Instant::now();
+        let moved = clock.tick(now);
+        self.clock_ticks += 1;
+        if moved || self.clock_ticks.is_multiple_of(CLOCK_BROADCAST_TICKS) {
+            let _ = self.events.send(TournamentEvent::Clock(clock.status(now)));
+        }
+
+        if moved {
+            self.update_level().await;
+            self.deal_next_hands().await;
+        }
+    }
+
+    /// New blinds reach each table before its next hand; hands in progress finish at the old level.
+    async fn update_level(&mut self) {
+        if let Some(clock) = self.tournament.clock_mut() {
+            clock.tick(Instant::now());
+        }
+        let Some((level, blinds)) = self.tournament.take_level_change() else { return; };
+
+        for entry in self.tables.values() {
+            let cmd = GameCommand::SetBlinds { blinds: blinds.clone() };
+            let _ = entry.handle.commands.send(cmd).await;
+        }
+
+        let _ = self.events.send(TournamentEvent::LevelChanged { level, blinds });
+    }
+
+    /// On the bubble every table plays one hand and then waits for the others.
+    fn update_hand_for_hand(&mut self) {
+        let bubble = self.tournament.config.payouts.len() + 1;
+        let active = self.tables.len() > 1 && self.tournament.remaining() == bubble;
+
+        if active != self.hand_for_hand {
+            self.hand_for_hand = active;
+            let _ = self.events.send(TournamentEvent::HandForHand { active });
+        }
+    }
+
+    async fn deal_next_hands(&mut self) {
+        if self.tournament.on_break() || self.tournament.deal_pending() {
+            return;
+        }
+        if self.hand_for_hand && self.tables.values().any(|t| !t.waiting) {
+            return;
+        }
+
+        for entry in self.tables.values_mut().filter(|t| t.waiting) {
+            let (tx, rx) = oneshot::channel();
+            if entry.handle.commands.send(GameCommand::StartHand { reply: tx }).await.is_err() {
+                continue;
+            }
+            // A table left with a single player stays idle until balancing brings it more.
+            if let Ok(true) = rx.await {
+                entry.waiting = false;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::Metrics;
+    use crate::shared::STARTING_BANKROLL;
+
+    fn recorder() -> TableRecorder {
+        TableRecorder {
+            histories: mpsc::unbounded_channel().0,
+            log: mpsc::unbounded_channel().0,
+            snapshots: mpsc::unbounded_channel().0,
+            ledger: mpsc::unbounded_channel().0,
+            metrics: Metrics::default(),
+        }
+    }
+
+    async fn purchase(director: &mpsc::Sender<DirectorCommand>, player_id: &str, purchase: Purchase) -> Result<(), String> {
+        let (reply, rx) = oneshot::channel();
+        director.send(DirectorCommand::Purchase { player_id: player_id.into(), purchase, reply }).await.unwrap();
+        rx.await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn entries_and_purchases_are_paid_from_the_bankroll() {
+        let accounts = AccountStore::open(":memory:").unwrap();
+        let config = serde_json::from_value(serde_json::json!({
+            "table_size": 2,
+            "tournament": {
+                "entrants": 2, "buy_in": 100, "starting_stack": 1000, "payouts": [100],
+                "schedule": { "levels": [{ "type": "level", "small_blind": 5, "big_blind": 10, "duration": { "minutes": 10 } }] },
+                "late_registration_level": 1, "rebuys": true,
+            },
+        })).unwrap();
+        let (director, _) = TournamentDirector::start(config, recorder(), accounts.clone()).unwrap();
+
+        let mut players = vec![];
+        for name in ["alice", "bob"] {
+            let id = accounts.register(name, "password").await.unwrap().id;
+            let (reply, rx) = oneshot::channel();
+            director.send(DirectorCommand::Register { player_id: id.clone(), reply }).await.unwrap();
+            rx.await.unwrap().unwrap();
+            players.push(id);
+        }
+
+        purchase(&director, &players[0], Purchase::Rebuy).await.unwrap();
+        assert!(purchase(&director, &players[0], Purchase::ReEntry).await.is_err());
+
+        for (id, spent) in players.iter().zip([200, 100]) {
+            assert_eq!(accounts.account(id).await.unwrap().unwrap().bankroll, STARTING_BANKROLL - spent);
+        }
+    }
+}
@@ -0,0 +1,268 @@
+//! Provably fair shuffling by commit-reveal.
+//!
+//! One hand ahead of time the table publishes `SHA-256(server_seed)`. Players may then set client
+//! seeds. The hand's deck seed is `SHA-256("deck" ‖ server_seed ‖ SHA-256(client_seed)…)` over the
+//! seeds of the players dealt in, in seat order; the prefix keeps it apart from the commitment.
+//! The deck starts in suit order (hearts, diamonds, clubs, spades, each two to ace) and is
+//! shuffled by Fisher–Yates from the last card down. Each swap index is drawn uniformly, by
+//! rejection sampling, from a stream of big-endian `u32`s whose block `k` is
+//! `SHA-256(deck_seed ‖ k as big-endian u64)`. Once the hand is over the server seed is revealed,
+//! so anyone can redo every step.
+
+use std::collections::HashMap;
+use rand::Rng;
+use ring::digest::{Context, SHA256, digest};
+use serde::{Serialize, Deserialize};
+
+use crate::domain::{Card, ClientSeed, Rank, ShuffleProof, Suit};
+use crate::game::constants::MAX_CLIENT_SEED_LEN;
+
+const DECK_SEED_PREFIX: &[u8] = b"deck";
+
+/// The committed server seed for the next hand and each player's latest client seed.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ShuffleSeeds {
+    next_server_seed: [u8; 32],
+    client_seeds: HashMap<String, String>,
+}
+
+impl ShuffleSeeds {
+    pub fn new(rng: &mut impl Rng) -> Self {
+        Self { next_server_seed: rng.gen(), client_seeds: HashMap::new() }
+    }
+
+    pub fn next_commitment(&self) -> String {
+        hex(digest(&SHA256, &self.next_server_seed).as_ref())
+    }
+
+    pub fn set_client_seed(&mut self, player_id: String, seed: String) -> Result<(), String> {
+        if seed.is_empty() || seed.len() > MAX_CLIENT_SEED_LEN {
+            return Err(format!("client seed must be 1 to {MAX_CLIENT_SEED_LEN} bytes"));
+        }
+        self.client_seeds.insert(player_id, seed);
+        Ok(())
+    }
+
+    /// Shuffles a hand's deck for `players`, in seat order, and commits to a fresh server seed
+    /// for the hand after it.
+    pub fn deal(&mut self, players: &[String], rng: &mut impl Rng) -> ShuffleProof {
+        let server_seed = std::mem::replace(&mut self.next_server_seed, rng.gen());
+        let client_seeds = players
+            .iter()
+            .filter_map(|id| self.client_seeds.get(id).map(|seed| ClientSeed { player_id: id.clone(), seed: seed.clone() }))
+            .collect();
+        prove(&server_seed, client_seeds)
+    }
+}
+
+pub fn prove(server_seed: &[u8], client_seeds: Vec<ClientSeed>) -> ShuffleProof {
+    let mut context = Context::new(&SHA256);
+    context.update(DECK_SEED_PREFIX);
+    context.update(server_seed);
+    for client in &client_seeds {
+        context.update(digest(&SHA256, client.seed.as_bytes()).as_ref());
+    }
+    let deck_seed = context.finish();
+
+    let mut deck = fresh_deck();
+    shuffle(&mut deck, deck_seed.as_ref());
+
+    ShuffleProof {
+        commitment: hex(digest(&SHA256, server_seed).as_ref()),
+        server_seed: hex(server_seed),
+        client_seeds,
+        deck_seed: hex(deck_seed.as_ref()),
+        deck,
+    }
+}
+
+/// Recomputes a revealed shuffle from its seeds.
+pub fn verify(proof: &ShuffleProof) -> Result<(), String> {
+    let server_seed = unhex(&proof.server_seed).ok_or("server seed is not hex")?;
+    let expected = prove(&server_seed, proof.client_seeds.clone());
+
+    if expected.commitment != proof.commitment {
+        return Err("server seed does not match the commitment".into());
+    }
+    if expected.deck_seed != proof.deck_seed {
+        return Err("deck seed does not follow from the server and client seeds".into());
+    }
+    if expected.deck != proof.deck {
+        return Err("deck does not follow from the deck seed".into());
+    }
+    Ok(())
+}
+
+/// The seed for the `round`th reshuffle of a draw game's discards: `SHA-256(deck_seed ‖ round)`.
+pub fn reshuffle_seed(proof: &ShuffleProof, round: u32) -> Vec<u8> {
+    let mut context = Context::new(&SHA256);
+    context.update(&unhex(&proof.deck_seed).unwrap_or_default());
+    context.update(&round.to_be_bytes());
+    context.finish().as_ref().to_vec()
+}
+
+pub fn fresh_deck() -> Vec<Card> {
+    let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+    let ranks = [
+        Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six,
+        Rank::Seven, Rank::Eight, Rank::Nine, Rank::Ten,
+        Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
+    ];
+
+    suits.iter().flat_map(|&suit| ranks.iter().map(move |&rank| Card { suit, rank })).collect()
+}
+
+pub fn shuffle<T>(items: &mut [T], seed: &[u8]) {
+    let mut stream = SeedStream { seed, block: 0, words: vec![] };
+    for i in (1..items.len()).rev() {
+        let bound = i as u64 + 1;
+        let zone = (1u64 << 32) / bound * bound;
+        let j = loop {
+            let x = u64::from(stream.next_u32());
+            if x < zone { break x % bound; }
+        };
+        items.swap(i, j as usize);
+    }
+}
+
+struct SeedStream<'a> {
+    seed: &'a [u8],
+    block: u64,
+    words: Vec<u32>,
+}
+
+impl SeedStream<'_> {
+    fn next_u32(&mut self) -> u32 {
+        if self.words.is_empty() {
+            let mut context = Context::new(&SHA256);
+            context.update(self.seed);
+            context.update(&self.block.to_be_bytes());
+            self.block += 1;
+            self.words = context
+                .finish()
+                .as_ref()
+                .chunks(4)
+                .rev()
+                .map(|word| u32::from_be_bytes([word[0], word[1], word[2], word[3]]))
+                .collect();
+        }
+        self.words.pop().expect("a block has eight words")
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) { return None; }
+    (0..text.len()).step_by(2).map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// Statistical checks that decks come out as uniform permutations. The default run is sized for
+/// `cargo test`; `cargo test --release -- --ignored shuffle_audit` runs millions of shuffles.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::RngSeed;
+    use crate::game::rng::table_rng;
+
+    const QUICK_SHUFFLES: usize = 20_000;
+    const AUDIT_SHUFFLES: usize = 2_000_000;
+    /// How many standard deviations a statistic may stray before the shuffle is called biased.
+    const Z_LIMIT: f64 = 4.0;
+    /// The 99.99th percentile of chi-square with 23 degrees of freedom.
+    const CHI_SQUARE_23_LIMIT: f64 = 57.07;
+
+    fn card_index(card: &Card) -> usize {
+        card.suit as usize * 13 + card.rank as usize
+    }
+
+    /// Deals the way `init_deck` does: server seeds from the table RNG, no client seeds.
+    fn decks(shuffles: usize) -> impl Iterator<Item = Vec<usize>> {
+        let mut rng = table_rng(RngSeed::Key([7; 32]));
+        let mut seeds = ShuffleSeeds::new(&mut rng);
+        (0..shuffles).map(move |_| seeds.deal(&[], &mut rng).deck.iter().map(card_index).collect())
+    }
+
+    /// Chi-square with many degrees of freedom, as a z-score.
+    fn chi_square_z(observed: &[u64], expected: f64, dof: f64) -> f64 {
+        let chi: f64 = observed.iter().map(|&o| (o as f64 - expected).powi(2) / expected).sum();
+        (chi - dof) / (2.0 * dof).sqrt()
+    }
+
+    fn card_positions(shuffles: usize) {
+        let mut counts = vec![0u64; 52 * 52];
+        for deck in decks(shuffles) {
+            for (position, card) in deck.into_iter().enumerate() {
+                counts[card * 52 + position] += 1;
+            }
+        }
+
+        let z = chi_square_z(&counts, shuffles as f64 / 52.0, 51.0 * 51.0);
+        assert!(z.abs() < Z_LIMIT, "card positions are not uniform: z = {z:.2}");
+    }
+
+    /// Wald–Wolfowitz runs of low (first 26) and high cards through each deck.
+    fn runs(shuffles: usize) {
+        let (n1, n2) = (26.0, 26.0);
+        let n = n1 + n2;
+        let mean = 2.0 * n1 * n2 / n + 1.0;
+        let variance = 2.0 * n1 * n2 * (2.0 * n1 * n2 - n) / (n * n * (n - 1.0));
+
+        let total: f64 = decks(shuffles)
+            .map(|deck| 1 + deck.windows(2).filter(|pair| (pair[0] < 26) != (pair[1] < 26)).count())
+            .map(|runs| runs as f64)
+            .sum();
+
+        let z = (total / shuffles as f64 - mean) / (variance / shuffles as f64).sqrt();
+        assert!(z.abs() < Z_LIMIT, "runs of low and high cards are off: z = {z:.2}");
+    }
+
+    /// Every ordering of four cards should turn up equally often.
+    fn small_permutations(shuffles: usize) {
+        let mut counts: HashMap<[usize; 4], u64> = HashMap::new();
+        for i in 0..shuffles {
+            let mut items = [0, 1, 2, 3];
+            shuffle(&mut items, &(i as u64).to_be_bytes());
+            *counts.entry(items).or_default() += 1;
+        }
+        assert_eq!(counts.len(), 24, "some orderings of four cards never came up");
+
+        let expected = shuffles as f64 / 24.0;
+        let chi: f64 = counts.values().map(|&o| (o as f64 - expected).powi(2) / expected).sum();
+        assert!(chi < CHI_SQUARE_23_LIMIT, "orderings of four cards are not uniform: chi-square = {chi:.1}");
+    }
+
+    #[test]
+    fn shuffles_are_uniform() {
+        card_positions(QUICK_SHUFFLES);
+        runs(QUICK_SHUFFLES);
+        small_permutations(QUICK_SHUFFLES * 12);
+    }
+
+    #[test]
+    #[ignore]
+    fn shuffle_audit() {
+        card_positions(AUDIT_SHUFFLES);
+        runs(AUDIT_SHUFFLES);
+        small_permutations(AUDIT_SHUFFLES * 12);
+    }
+
+    #[test]
+    fn revealed_shuffles_verify() {
+        let mut rng = table_rng(RngSeed::Key([1; 32]));
+        let mut seeds = ShuffleSeeds::new(&mut rng);
+        let committed = seeds.next_commitment();
+        seeds.set_client_seed("p1".into(), "lucky".into()).unwrap();
+
+        let proof = seeds.deal(&["p1".into(), "p2".into()], &mut rng);
+        assert_eq!(proof.commitment, committed);
+        assert_ne!(proof.deck_seed, proof.commitment);
+        assert!(verify(&proof).is_ok());
+
+        let mut forged = proof.clone();
+        forged.client_seeds[0].seed = "unlucky".into();
+        assert!(verify(&forged).is_err());
+    }
+}
@@ -1,144 +1,1797 @@
-use tokio::sync::{mpsc, broadcast};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use tokio::sync::{mpsc, broadcast, oneshot};
 use uuid::Uuid;
-use rand::{rngs::StdRng, SeedableRng};
+use rand::Rng;
 use rand::seq::SliceRandom;
 
 use crate::domain::{
     Card, PlayerStatus, PrivateState, PublicGameState, PublicPlayer,
-    Rank, RoundPhase, ServerEvent, Suit, CardStore, PlayerAction,
-    GameCommand
+    RoundPhase, ServerEvent, CardStore, PlayerAction,
+    GameCommand, PotAward, RevealedHand, BettingStructure, TableConfig,
+    VariantSpec, TournamentEvent, HandReport, ReleasedPlayer, TableHandle, BlindLevel,
+    Purchase, DealAction, HandHistory, SeatRecord, ActionRecord, RecordedAction, GameLogEntry, LoggedCommand,
+    CashOut, JournalEntry, JournalKind, LedgerAccount, LedgerRecord, RngSeed,
 };
+use crate::game::fairness::{self, ShuffleSeeds};
+use crate::game::rng::{TableRng, os_seed, table_rng};
 
-use crate::game::constants::{COMMAND_CHANNEL_CAPACITY, FLOP_CARDS, MAX_PLAYERS};
+use crate::game::constants::{
+    BOARD_CARDS, CLOCK_BROADCAST_TICKS, CLOCK_TICK_SECS, COMMAND_CHANNEL_CAPACITY, FIXED_LIMIT_BET_CAP, FLOP_CARDS, MAX_PLAYERS,
+    RESEED_INTERVAL_SECS, SNAPSHOT_INTERVAL_SECS,
+};
+use crate::game::hand_evaluator::evaluate;
+use crate::game::pot::{Pot, build_pots};
+use crate::game::rake::{rake, take_rake};
+use crate::game::recovery::{RecoveryPolicy, TableRecorder, TableSnapshot};
+use crate::game::rotation::Rotation;
+use crate::game::tournament::Tournament;
+
+pub struct GameManager {
+    pub state: PublicGameState,
+    pub cards: CardStore,
+    pub broadcaster: broadcast::Sender<ServerEvent>,
+    rng: TableRng,
+    shuffle: ShuffleSeeds,
+    /// Rekeys from its own RNG rather than the operating system, so runs repeat exactly.
+    deterministic: bool,
+    stacked_decks: VecDeque<Vec<Card>>,
+    contributions: HashMap<String, u64>,
+    acted: HashSet<String>,
+    min_raise: u64,
+    bets_this_round: u32,
+    rotation: Rotation,
+    max_boards: u8,
+    runout_votes: HashMap<String, u8>,
+    tournament: Option<Tournament>,
+    away: HashSet<String>,
+    /// What each player bought in with from their bankroll; their stack goes back to it when they leave.
+    banked: HashMap<String, u64>,
+    /// Sit-and-go entry fees paid from a bankroll, refunded to players who leave before the start.
+    entry_fees: HashMap<String, u64>,
+    /// Players an operator banned from the table.
+    banned: HashSet<String>,
+    paused: bool,
+    /// Set once an operator closes the table; the actor stops after the command.
+    closed: bool,
+    reports: Option<(usize, mpsc::UnboundedSender<HandReport>)>,
+    pending_blinds: Option<BlindLevel>,
+    pending_chips: HashMap<String, u64>,
+    big_blind_seat: usize,
+    hand_number: u64,
+    clock_ticks: u64,
+    hand_log: Option<HandHistory>,
+    table_id: String,
+    seed: RngSeed,
+    opened_at: Instant,
+    now: Instant,
+    /// Table time already elapsed when this process took the table over.
+    resumed_from: Duration,
+    log_sequence: u64,
+    snapshot_sequence: u64,
+    reseed_sequence: u64,
+    recorder: Option<TableRecorder>,
+    /// Set once the server starts shutting down: no more players are seated or hands dealt.
+    draining: bool,
+    drained: Option<oneshot::Sender<()>>,
+}
+
+impl GameManager {
+    pub fn new(config: TableConfig) -> Self {
+        Self::with_seed(config, os_seed())
+    }
+
+    pub fn with_seed(config: TableConfig, seed: RngSeed) -> Self {
+        let opened_at = Instant::now();
+        let (tx, _) = broadcast::channel(128);
+        let max_boards = config.max_boards;
+        let tournament = config.sit_and_go.clone().map(Tournament::new);
+        let rotation = Rotation::new(config);
+        let spec = rotation.current().clone();
+        let mut rng = table_rng(seed);
+        let shuffle = ShuffleSeeds::new(&mut rng);
+        Self {
+            state: PublicGameState {
+                variant: spec.variant,
+                betting_structure: spec.structure,
+                players: vec![],
+                community_cards: vec![],
+                boards: vec![],
+                pot: 0,
+                dealer_seat: 0,
+                current_turn_seat: None,
+                phase: RoundPhase::Waiting,
+                small_blind_amount: spec.small_blind,
+                big_blind_amount: spec.big_blind,
+                ante_amount: 0,
+                current_bet: 0,
+                next_shuffle_commitment: shuffle.next_commitment(),
+            },
+            cards: CardStore::new(),
+            broadcaster: tx,
+            rng,
+            shuffle,
+            deterministic: false,
+            stacked_decks: VecDeque::new(),
+            contributions: HashMap::new(),
+            acted: HashSet::new(),
+            min_raise: 0,
+            bets_this_round: 0,
+            rotation,
+            max_boards,
+            runout_votes: HashMap::new(),
+            tournament,
+            away: HashSet::new(),
+            banked: HashMap::new(),
+            entry_fees: HashMap::new(),
+            banned: HashSet::new(),
+            paused: false,
+            closed: false,
+            reports: None,
+            pending_blinds: None,
+            pending_chips: HashMap::new(),
+            big_blind_seat: 0,
+            hand_number: 0,
+            clock_ticks: 0,
+            hand_log: None,
+            table_id: String::new(),
+            seed,
+            opened_at,
+            now: opened_at,
+            resumed_from: Duration::ZERO,
+            log_sequence: 0,
+            snapshot_sequence: 0,
+            reseed_sequence: 0,
+            recorder: None,
+            draining: false,
+            drained: None,
+        }
+    }
+
+    /// A table that deals the same cards every run, for tests.
+    pub fn seeded(config: TableConfig, seed: u64) -> Self {
+        let mut manager = Self::with_seed(config, RngSeed::Number(seed));
+        manager.deterministic = true;
+        manager
+    }
+
+    pub fn start(
+        config: TableConfig,
+        table_id: String,
+        recorder: TableRecorder,
+    ) -> (mpsc::Sender<GameCommand>, broadcast::Sender<ServerEvent>) {
+        GameManager::new(config).launch(table_id, recorder)
+    }
+
+    /// Runs an already built table, such as a seeded one, as an actor.
+    pub fn launch(
+        mut self,
+        table_id: String,
+        recorder: TableRecorder,
+    ) -> (mpsc::Sender<GameCommand>, broadcast::Sender<ServerEvent>) {
+        let config = self.rotation.config().clone();
+        self.open_log(table_id, config, recorder);
+        for cards in self.stacked_decks.clone() {
+            self.record(LoggedCommand::StackDeck { cards });
+        }
+        self.spawn()
+    }
+
+    /// Deals the next hand not yet stacked from `cards`, before the table is launched: they come
+    /// off the deck in the order given and the rest of the deck follows, shuffled. Each player in
+    /// seat order gets all their hole cards at once, and a card is burned before each street.
+    /// Stacked hands carry no shuffle proof.
+    pub fn stack_deck(&mut self, cards: Vec<Card>) -> Result<(), String> {
+        let mut seen = HashSet::new();
+        if let Some(card) = cards.iter().find(|&card| !seen.insert(card)) {
+            return Err(format!("{card} is stacked twice"));
+        }
+        self.stacked_decks.push_back(cards);
+        Ok(())
+    }
+
+    /// Rebuilds a table from its latest snapshot and the log written after it, settles the hand
+    /// the restart interrupted and starts the table again. Seated players are away until they
+    /// come back.
+    pub fn recover(
+        snapshot: Option<TableSnapshot>,
+        log: &[GameLogEntry],
+        policy: RecoveryPolicy,
+        recorder: TableRecorder,
+    ) -> Result<(mpsc::Sender<GameCommand>, broadcast::Sender<ServerEvent>), String> {
+        let mut manager = GameManager::rebuild(snapshot, log)?;
+        manager.go_live();
+        manager.recorder = Some(recorder);
+        manager.draining = false;
+
+        if manager.hand_in_progress() && policy == RecoveryPolicy::Void {
+            manager.void_hand();
+            manager.record(LoggedCommand::VoidHand);
+        }
+        manager.restart();
+        manager.record(LoggedCommand::Restart);
+
+        tracing::info!(table_id = %manager.table_id, hand_number = manager.hand_number, ?policy, "table recovered");
+        Ok(manager.spawn())
+    }
+
+    fn spawn(mut self) -> (mpsc::Sender<GameCommand>, broadcast::Sender<ServerEvent>) {
+        let (tx_cmd, rx_cmd) = mpsc::channel::<GameCommand>(COMMAND_CHANNEL_CAPACITY);
+        let broadcaster = self.broadcaster.clone();
+
+        tokio::spawn(async move { self.run(rx_cmd).await; });
+
+        (tx_cmd, broadcaster)
+    }
+
+    /// Starts a table whose seating and hand pacing are driven by a tournament director.
+    pub fn start_managed(
+        config: TableConfig,
+        table_id: usize,
+        reports: mpsc::UnboundedSender<HandReport>,
+        recorder: TableRecorder,
+    ) -> TableHandle {
+        let mut manager = GameManager::new(config.clone());
+        manager.reports = Some((table_id, reports));
+        manager.open_log(format!("tournament-{table_id}"), config, recorder);
+        let (commands, events) = manager.spawn();
+
+        TableHandle { table_id, commands, events }
+    }
+
+    async fn run(&mut self, mut rx: mpsc::Receiver<GameCommand>) {
+        let mut clock = tokio::time::interval(Duration::from_secs(CLOCK_TICK_SECS));
+        let mut snapshots = tokio::time::interval(Duration::from_secs(SNAPSHOT_INTERVAL_SECS));
+        let mut reseeds = tokio::time::interval(Duration::from_secs(RESEED_INTERVAL_SECS));
+        let metrics = self.recorder.as_ref().map(|recorder| recorder.metrics.clone());
+        let queue_depth = metrics.as_ref().map(|m| m.command_queue_depth.with_label_values(&[&self.table_id]));
+        if let Some(metrics) = &metrics {
+            metrics.active_tables.inc();
+        }
+
+        loop {
+            tokio::select! {
+                cmd = rx.recv() => match cmd {
+                    Some(cmd) => {
+                        self.handle_command(cmd);
+                        if self.closed {
+                            break;
+                        }
+                        if let Some(depth) = &queue_depth {
+                            depth.set(rx.len() as i64);
+                        }
+                    }
+                    None => break,
+                },
+                _ = clock.tick() => {
+                    self.now = Instant::now();
+                    if self.on_clock_tick() {
+                        self.record(LoggedCommand::ClockTick);
+                    }
+                    self.act_if_away();
+                }
+                _ = snapshots.tick() => {
+                    self.now = Instant::now();
+                    self.take_snapshot();
+                }
+                _ = reseeds.tick() => {
+                    // An idle table has drawn nothing since the last rekeying.
+                    if self.log_sequence != self.reseed_sequence {
+                        self.now = Instant::now();
+                        self.reseed();
+                    }
+                }
+            }
+            if self.draining && !self.hand_in_progress() {
+                self.finish_drain();
+            }
+        }
+        if let Some(metrics) = &metrics {
+            metrics.active_tables.dec();
+            let _ = metrics.command_queue_depth.remove_label_values(&[&self.table_id]);
+        }
+        tracing::info!(table_id = %self.table_id, closed = self.closed, "GameManager actor exiting");
+    }
+
+    /// Rebuilds a table from its snapshot and the log entries after it. The log is cut back at
+    /// each snapshot, so only a log that was never cut can be replayed without one.
+    pub fn rebuild(snapshot: Option<TableSnapshot>, log: &[GameLogEntry]) -> Result<GameManager, String> {
+        let matches_log = |s: &TableSnapshot| log.iter().any(|e| {
+            e.sequence == s.sequence && matches!(e.command, LoggedCommand::Reseed { seed } if seed == s.seed)
+        });
+        match snapshot.filter(matches_log) {
+            Some(snapshot) => {
+                let sequence = snapshot.sequence;
+                let mut manager = GameManager::restore(snapshot);
+                let rest: Vec<GameLogEntry> = log.iter().filter(|e| e.sequence > sequence).cloned().collect();
+                manager.apply_entries(&rest)?;
+                Ok(manager)
+            }
+            None => GameManager::replay(log),
+        }
+    }
+
+    /// Rebuilds a table by replaying its event log into a fresh manager.
+    fn replay(entries: &[GameLogEntry]) -> Result<GameManager, String> {
+        let (first, rest) = entries.split_first().ok_or("empty game log")?;
+        let LoggedCommand::Opened { config, seed, managed } = &first.command else {
+            return Err("game log must start with the table opening".into());
+        };
+
+        let mut manager = GameManager::with_seed(config.as_ref().clone(), *seed);
+        manager.table_id = first.table_id.clone();
+        manager.log_sequence = first.sequence + 1;
+        if let Some(table_id) = managed {
+            manager.reports = Some((*table_id, mpsc::unbounded_channel().0));
+        }
+
+        manager.apply_entries(rest)?;
+        Ok(manager)
+    }
+
+    fn apply_entries(&mut self, entries: &[GameLogEntry]) -> Result<(), String> {
+        for entry in entries {
+            if entry.sequence != self.log_sequence {
+                return Err(format!("game log gap before entry {}", entry.sequence));
+            }
+            self.log_sequence = entry.sequence + 1;
+            let elapsed = Duration::from_nanos(entry.elapsed_ns).saturating_sub(self.resumed_from);
+            self.now = self.opened_at + elapsed;
+            self.apply_logged(entry.command.clone());
+        }
+        Ok(())
+    }
+
+    fn restore(snapshot: TableSnapshot) -> GameManager {
+        let mut manager = GameManager::with_seed(snapshot.config, snapshot.seed);
+        manager.table_id = snapshot.table_id;
+        manager.log_sequence = snapshot.sequence + 1;
+        manager.snapshot_sequence = manager.log_sequence;
+        manager.reseed_sequence = manager.log_sequence;
+        manager.resumed_from = Duration::from_nanos(snapshot.elapsed_ns);
+        manager.reports = snapshot.managed.map(|table_id| (table_id, mpsc::unbounded_channel().0));
+        manager.max_boards = snapshot.max_boards;
+        manager.state = snapshot.state;
+        manager.cards = snapshot.cards;
+        manager.contributions = snapshot.contributions;
+        manager.acted = snapshot.acted;
+        manager.min_raise = snapshot.min_raise;
+        manager.bets_this_round = snapshot.bets_this_round;
+        manager.rotation = snapshot.rotation;
+        manager.runout_votes = snapshot.runout_votes;
+        manager.tournament = snapshot.tournament;
+        manager.away = snapshot.away;
+        manager.banked = snapshot.banked;
+        manager.entry_fees = snapshot.entry_fees;
+        manager.banned = snapshot.banned;
+        manager.paused = snapshot.paused;
+        manager.pending_blinds = snapshot.pending_blinds;
+        manager.pending_chips = snapshot.pending_chips;
+        manager.big_blind_seat = snapshot.big_blind_seat;
+        manager.hand_number = snapshot.hand_number;
+        manager.hand_log = snapshot.hand_log;
+        manager.deterministic = snapshot.deterministic;
+        manager.stacked_decks = snapshot.stacked_decks;
+        if let Some(shuffle) = snapshot.shuffle {
+            // The seed drawn when the manager was built is replaced, so the draw must not count.
+            manager.shuffle = shuffle;
+            manager.rng = table_rng(snapshot.seed);
+        }
+
+        if let (Some(tournament), Some(clock)) = (&mut manager.tournament, snapshot.clock) {
+            tournament.restore_clock(clock, manager.now);
+        }
+        manager
+    }
+
+    fn snapshot(&self) -> TableSnapshot {
+        TableSnapshot {
+            table_id: self.table_id.clone(),
+            sequence: self.log_sequence - 1,
+            elapsed_ns: self.elapsed().as_nanos() as u64,
+            taken_at: Utc::now(),
+            config: self.rotation.config().clone(),
+            seed: self.seed,
+            managed: self.reports.as_ref().map(|(table_id, _)| *table_id),
+            max_boards: self.max_boards,
+            state: self.state.clone(),
+            cards: self.cards.clone(),
+            contributions: self.contributions.clone(),
+            acted: self.acted.clone(),
+            min_raise: self.min_raise,
+            bets_this_round: self.bets_this_round,
+            rotation: self.rotation.clone(),
+            runout_votes: self.runout_votes.clone(),
+            tournament: self.tournament.clone(),
+            clock: self.tournament.as_ref().and_then(|t| t.clock_snapshot(self.now)),
+            away: self.away.clone(),
+            banked: self.banked.clone(),
+            entry_fees: self.entry_fees.clone(),
+            banned: self.banned.clone(),
+            paused: self.paused,
+            pending_blinds: self.pending_blinds.clone(),
+            pending_chips: self.pending_chips.clone(),
+            big_blind_seat: self.big_blind_seat,
+            hand_number: self.hand_number,
+            hand_log: self.hand_log.clone(),
+            shuffle: Some(self.shuffle.clone()),
+            deterministic: self.deterministic,
+            stacked_decks: self.stacked_decks.clone(),
+        }
+    }
+
+    /// Reseeds first so the snapshot and the log agree on every number still to be drawn.
+    fn take_snapshot(&mut self) {
+        if self.recorder.is_none() || self.log_sequence == self.snapshot_sequence { return; }
+
+        self.reseed();
+        self.snapshot_sequence = self.log_sequence;
+
+        let snapshot = self.snapshot();
+        if let Some(recorder) = &self.recorder {
+            let _ = recorder.snapshots.send(snapshot);
+        }
+    }
+
+    fn drain(&mut self, reply: oneshot::Sender<()>) {
+        self.draining = true;
+        self.drained = Some(reply);
+        let _ = self.broadcaster.send(ServerEvent::ServerShuttingDown);
+    }
+
+    /// Saves the idle table and tells whoever asked for the drain.
+    fn finish_drain(&mut self) {
+        let Some(reply) = self.drained.take() else { return; };
+        self.take_snapshot();
+        let _ = reply.send(());
+    }
+
+    fn reseed(&mut self) {
+        self.seed = if self.deterministic { RngSeed::Key(self.rng.gen()) } else { os_seed() };
+        self.rng = table_rng(self.seed);
+        self.record(LoggedCommand::Reseed { seed: self.seed });
+        self.reseed_sequence = self.log_sequence;
+    }
+
+    /// Moves the table onto this process's clock; blind levels do not run while the server is down.
+    fn go_live(&mut self) {
+        let now = Instant::now();
+        let clock = self.tournament.as_ref().and_then(|t| t.clock_snapshot(self.now));
+        self.resumed_from = self.elapsed();
+        self.opened_at = now;
+        self.now = now;
+        if let (Some(tournament), Some(clock)) = (&mut self.tournament, clock) {
+            tournament.restore_clock(clock, now);
+        }
+    }
+
+    fn elapsed(&self) -> Duration {
+        self.resumed_from + self.now.duration_since(self.opened_at)
+    }
+
+    /// Calls off the current hand and hands every bet, blind and ante back.
+    fn void_hand(&mut self) {
+        for seat in 0..self.state.players.len() {
+            let refund = self.contributions.remove(&self.state.players[seat].id).unwrap_or(0);
+            self.journal(JournalKind::VoidRefund, self.pot_account(), self.stack_account(seat), refund);
+            let player = &mut self.state.players[seat];
+            player.stack += refund;
+            player.committed = 0;
+            player.cards_drawn.clear();
+            if matches!(player.status, PlayerStatus::Active | PlayerStatus::Folded | PlayerStatus::AllIn) {
+                player.status = PlayerStatus::Waiting;
+            }
+        }
+
+        self.contributions.clear();
+        self.acted.clear();
+        self.runout_votes.clear();
+        self.reveal_shuffle(None);
+        self.cards = CardStore::new();
+        self.hand_log = None;
+        self.state.pot = 0;
+        self.state.current_bet = 0;
+        self.state.community_cards.clear();
+        self.state.boards.clear();
+        self.state.current_turn_seat = None;
+        self.state.phase = RoundPhase::Waiting;
+
+        let _ = self.broadcaster.send(ServerEvent::HandVoided { hand_number: self.hand_number });
+        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+    }
+
+    fn apply_logged(&mut self, command: LoggedCommand) {
+        match command {
+            LoggedCommand::Opened { .. } => {}
+            LoggedCommand::Join { player_id, stack, banked } => { let _ = self.add_waiting_player(player_id, stack, banked); }
+            LoggedCommand::Action { player_id, action } => { let _ = self.handle_action(&player_id, action); }
+            LoggedCommand::Disconnect { player_id } => self.handle_disconnect(&player_id),
+            LoggedCommand::Reconnect { player_id } => { let _ = self.reconnect(&player_id); }
+            LoggedCommand::Restart => self.restart(),
+            LoggedCommand::SeatPlayer { player_id, stack, away } => { let _ = self.seat_player(player_id, stack, away); }
+            LoggedCommand::ReleasePlayer => { let _ = self.release_player(); }
+            LoggedCommand::SetBlinds { blinds } => self.pending_blinds = Some(blinds),
+            LoggedCommand::StartHand => self.start_new_round(),
+            LoggedCommand::AddChips { player_id, amount } => self.add_chips(&player_id, amount),
+            LoggedCommand::Purchase { player_id, purchase } => { let _ = self.purchase(&player_id, purchase); }
+            LoggedCommand::Deal { player_id, action } => { let _ = self.handle_deal(&player_id, action); }
+            LoggedCommand::ClockTick => { self.on_clock_tick(); }
+            LoggedCommand::Reseed { seed } => {
+                self.seed = seed;
+                self.rng = table_rng(seed);
+            }
+            LoggedCommand::VoidHand => self.void_hand(),
+            LoggedCommand::ClientSeed { player_id, seed } => { let _ = self.set_client_seed(player_id, seed); }
+            LoggedCommand::StackDeck { cards } => { let _ = self.stack_deck(cards); }
+            LoggedCommand::Drain => self.draining = true,
+            LoggedCommand::Pause => { let _ = self.pause(); }
+            LoggedCommand::Resume => { let _ = self.resume(); }
+            LoggedCommand::Kick { player_id } => { let _ = self.remove_player(&player_id, false); }
+            LoggedCommand::Ban { player_id } => { let _ = self.remove_player(&player_id, true); }
+            LoggedCommand::AdjustStack { player_id, amount, reason, admin } => { let _ = self.adjust_stack(&player_id, amount, &reason, &admin); }
+            LoggedCommand::ChangeBlinds { blinds } => { let _ = self.change_blinds(blinds); }
+            LoggedCommand::Close => { let _ = self.close(); }
+        }
+    }
+
+    fn open_log(&mut self, table_id: String, config: TableConfig, recorder: TableRecorder) {
+        let managed = self.reports.as_ref().map(|(id, _)| *id);
+        self.table_id = table_id;
+        self.recorder = Some(recorder);
+        self.record(LoggedCommand::Opened { config: Box::new(config), seed: self.seed, managed });
+    }
+
+    fn record(&mut self, command: LoggedCommand) {
+        let Some(recorder) = &self.recorder else { return; };
+        let entry = GameLogEntry {
+            table_id: self.table_id.clone(),
+            sequence: self.log_sequence,
+            elapsed_ns: self.elapsed().as_nanos() as u64,
+            command,
+        };
+        let _ = recorder.log.send(entry);
+        self.log_sequence += 1;
+    }
+
+    fn handle_command(&mut self, cmd: GameCommand) {
+        self.now = Instant::now();
+        match cmd {
+            GameCommand::Join { account_id, stack, reply } => {
+                let res = self.add_waiting_player(account_id.clone(), stack, true);
+                if res.is_ok() {
+                    self.record(LoggedCommand::Join { player_id: account_id, stack, banked: true });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Action { player_id, action, reply } => {
+                let res = self.handle_action(&player_id, action.clone());
+                if res.is_ok() {
+                    self.record(LoggedCommand::Action { player_id, action });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Disconnect { player_id } => {
+                self.handle_disconnect(&player_id);
+                self.record(LoggedCommand::Disconnect { player_id });
+            }
+            GameCommand::Reconnect { player_id, reply } => {
+                let res = self.reconnect(&player_id);
+                if res == Ok(true) {
+                    self.record(LoggedCommand::Reconnect { player_id });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::GetPrivateState { player_id, reply } => {
+                let private = self.private_state(&player_id);
+                let _ = reply.send(private);
+            }
+            GameCommand::GetState { reply } => {
+                let _ = reply.send(self.masked_state());
+            }
+            GameCommand::PlayerChips { player_id, reply } => {
+                let _ = reply.send(self.player_chips(&player_id));
+            }
+            GameCommand::Reconcile { reply } => {
+                let Some(recorder) = &self.recorder else { return; };
+                let stacks = self.state.players.iter().map(|p| p.stack).sum();
+                let checkpoint = LedgerRecord::Checkpoint { table_id: self.table_id.clone(), stacks, pot: self.state.pot, reply };
+                let _ = recorder.ledger.send(checkpoint);
+            }
+            GameCommand::Drain { reply } => {
+                self.drain(reply);
+                self.record(LoggedCommand::Drain);
+            }
+            GameCommand::SeatPlayer { player_id, stack, away, reply } => {
+                let res = self.seat_player(player_id.clone(), stack, away);
+                if res.is_ok() {
+                    self.record(LoggedCommand::SeatPlayer { player_id, stack, away });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::ReleasePlayer { reply } => {
+                let res = self.release_player();
+                if res.is_ok() {
+                    self.record(LoggedCommand::ReleasePlayer);
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::SetBlinds { blinds } => {
+                self.pending_blinds = Some(blinds.clone());
+                self.record(LoggedCommand::SetBlinds { blinds });
+            }
+            GameCommand::StartHand { reply } => {
+                let before = self.hand_number;
+                if !self.hand_in_progress() {
+                    self.start_new_round();
+                    self.record(LoggedCommand::StartHand);
+                }
+                let _ = reply.send(self.hand_number != before);
+            }
+            GameCommand::AddChips { player_id, amount } => {
+                self.add_chips(&player_id, amount);
+                self.record(LoggedCommand::AddChips { player_id, amount });
+            }
+            GameCommand::Purchase { player_id, purchase, reply } => {
+                let res = self.purchase(&player_id, purchase);
+                if res.is_ok() {
+                    self.record(LoggedCommand::Purchase { player_id, purchase });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Deal { player_id, action, reply } => {
+                let res = self.handle_deal(&player_id, action.clone());
+                if res.is_ok() {
+                    self.record(LoggedCommand::Deal { player_id, action });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::SetClientSeed { player_id, seed, reply } => {
+                let res = self.set_client_seed(player_id.clone(), seed.clone());
+                if res.is_ok() {
+                    self.record(LoggedCommand::ClientSeed { player_id, seed });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Pause { reply } => {
+                let res = self.pause();
+                if res.is_ok() {
+                    self.record(LoggedCommand::Pause);
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Resume { reply } => {
+                let res = self.resume();
+                if res.is_ok() {
+                    self.record(LoggedCommand::Resume);
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Kick { player_id, reply } => {
+                let res = self.remove_player(&player_id, false);
+                if res.is_ok() {
+                    self.record(LoggedCommand::Kick { player_id });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Ban { player_id, reply } => {
+                let res = self.remove_player(&player_id, true);
+                if res.is_ok() {
+                    self.record(LoggedCommand::Ban { player_id });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::AdjustStack { player_id, amount, reason, admin, reply } => {
+                let res = self.adjust_stack(&player_id, amount, &reason, &admin);
+                if res.is_ok() {
+                    tracing::info!(table_id = %self.table_id, %player_id, amount, %reason, %admin, "stack adjusted");
+                    self.record(LoggedCommand::AdjustStack { player_id, amount, reason, admin });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::VoidHand { reply } => {
+                let res = self.void_current_hand();
+                if res.is_ok() {
+                    self.record(LoggedCommand::VoidHand);
+                    self.start_new_round();
+                    self.record(LoggedCommand::StartHand);
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Announce { message } => {
+                let _ = self.broadcaster.send(ServerEvent::Announcement { message });
+            }
+            GameCommand::ChangeBlinds { blinds, reply } => {
+                let res = self.change_blinds(blinds.clone());
+                if res.is_ok() {
+                    self.record(LoggedCommand::ChangeBlinds { blinds });
+                }
+                let _ = reply.send(res);
+            }
+            GameCommand::Close { reply } => {
+                let res = self.close();
+                if res.is_ok() {
+                    self.record(LoggedCommand::Close);
+                }
+                let _ = reply.send(res);
+            }
+        }
+    }
+
+    fn pause(&mut self) -> Result<(), String> {
+        if self.paused {
+            return Err("the table is already paused".into());
+        }
+        self.paused = true;
+        let _ = self.broadcaster.send(ServerEvent::TablePaused);
+        Ok(())
+    }
+
+    /// Picks the hand up where it stopped, or deals the next one.
+    fn resume(&mut self) -> Result<(), String> {
+        if !self.paused {
+            return Err("the table is not paused".into());
+        }
+        self.paused = false;
+        let _ = self.broadcaster.send(ServerEvent::TableResumed);
+
+        if !self.hand_in_progress() && self.tournament.as_ref().is_none_or(Tournament::is_running) {
+            self.start_new_round();
+        }
+        Ok(())
+    }
+
+    /// Takes a cash player's seat the way leaving does. A player can be banned without being
+    /// seated.
+    fn remove_player(&mut self, player_id: &str, ban: bool) -> Result<(), String> {
+        if self.tournament.is_some() || self.reports.is_some() {
+            return Err("tournament players cannot be removed".into());
+        }
+        let seated = self.state.players.iter().any(|p| p.id == player_id && p.status != PlayerStatus::SittingOut);
+        if !seated && !ban {
+            return Err("not seated at this table".into());
+        }
+
+        if ban {
+            self.banned.insert(player_id.to_string());
+        }
+        let _ = self.broadcaster.send(ServerEvent::PlayerRemoved { player_id: player_id.to_string(), banned: ban });
+        if seated {
+            self.handle_disconnect(player_id);
+        }
+        Ok(())
+    }
+
+    /// Stands every player up and cashes them out, so the table can be taken down. Only between
+    /// hands: a hand in progress has to finish or be voided first.
+    fn close(&mut self) -> Result<(), String> {
+        if self.tournament.is_some() || self.reports.is_some() {
+            return Err("tournament tables close when the tournament is over".into());
+        }
+        if self.hand_in_progress() {
+            return Err("a hand is in progress; void it or let it finish first".into());
+        }
+
+        let seated: Vec<String> = self.state.players
+            .iter()
+            .filter(|p| p.status != PlayerStatus::SittingOut)
+            .map(|p| p.id.clone())
+            .collect();
+        for player_id in seated {
+            self.remove_player(&player_id, false)?;
+        }
+        self.closed = true;
+        let _ = self.broadcaster.send(ServerEvent::TableClosed);
+        Ok(())
+    }
+
+    /// Only between the player's hands, so nothing they have committed changes under them.
+    fn adjust_stack(&mut self, player_id: &str, amount: i64, reason: &str, admin: &str) -> Result<u64, String> {
+        if self.tournament.is_some() || self.reports.is_some() {
+            return Err("tournament stacks cannot be adjusted".into());
+        }
+        if reason.trim().is_empty() {
+            return Err("an adjustment needs a reason".into());
+        }
+        if amount == 0 {
+            return Err("nothing to adjust".into());
+        }
+        let seat = self.state.players
+            .iter()
+            .position(|p| p.id == player_id && p.status != PlayerStatus::SittingOut)
+            .ok_or("not seated at this table")?;
+        if self.hand_in_progress() && Self::in_hand(&self.state.players[seat]) {
+            return Err("the player is in a hand".into());
+        }
+        let stack = self.state.players[seat]
+            .stack
+            .checked_add_signed(amount)
+            .ok_or("the adjustment would leave a negative stack")?;
+
+        let (from, to) = if amount > 0 {
+            (LedgerAccount::Adjustments, self.stack_account(seat))
+        } else {
+            (self.stack_account(seat), LedgerAccount::Adjustments)
+        };
+        let memo = format!("by {admin}: {reason}");
+        self.journal_with_memo(JournalKind::Adjustment, from, to, amount.unsigned_abs(), Some(memo));
+        self.state.players[seat].stack = stack;
+
+        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+        Ok(stack)
+    }
+
+    fn void_current_hand(&mut self) -> Result<(), String> {
+        if self.reports.is_some() {
+            return Err("tournament hands are run by the director".into());
+        }
+        if !self.hand_in_progress() {
+            return Err("no hand in progress".into());
+        }
+        self.void_hand();
+        Ok(())
+    }
+
+    fn change_blinds(&mut self, blinds: BlindLevel) -> Result<(), String> {
+        if self.tournament.is_some() || self.reports.is_some() {
+            return Err("a tournament's blinds follow its clock".into());
+        }
+        if self.rotation.config().is_mixed() {
+            return Err("mixed tables take their blinds from each game".into());
+        }
+        if blinds.small_blind == 0 || blinds.big_blind < blinds.small_blind {
+            return Err("blinds must be positive and the big blind at least the small blind".into());
+        }
+
+        if self.hand_in_progress() {
+            self.pending_blinds = Some(blinds);
+        } else {
+            self.apply_blinds(&blinds);
+            let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+        }
+        Ok(())
+    }
+
+    /// Counts toward the next hand dealt; the commitment for that hand is already public.
+    fn set_client_seed(&mut self, player_id: String, seed: String) -> Result<(), String> {
+        if !self.state.players.iter().any(|p| p.id == player_id) {
+            return Err("not seated at this table".into());
+        }
+        self.shuffle.set_client_seed(player_id, seed)
+    }
+
+    fn add_waiting_player(&mut self, player_id: String, stack: u64, banked: bool) -> Result<String, String> {
+        if self.reports.is_some() {
+            return Err("seats are assigned by the tournament director".into());
+        }
+        if self.draining {
+            return Err("the server is shutting down".into());
+        }
+        if self.banned.contains(&player_id) {
+            return Err("banned from this table".into());
+        }
+
+        let own_seat = self.state.players.iter().position(|p| p.id == player_id);
+        if own_seat.is_some_and(|seat| self.state.players[seat].status != PlayerStatus::SittingOut) {
+            return Err("already seated at this table".into());
+        }
+
+        let free_seat = match &self.tournament {
+            Some(_) => own_seat,
+            None => own_seat.or_else(|| self.state.players.iter().position(|p| p.status == PlayerStatus::SittingOut)),
+        };
+        if free_seat.is_none() && self.state.players.len() >= MAX_PLAYERS {
+            return Err("Table full".into());
+        }
+
+        let seat = free_seat.unwrap_or(self.state.players.len());
+        if seat < self.state.players.len() {
+            self.cash_out(seat);
+        }
+
+        let stack = match &mut self.tournament {
+            Some(tournament) => {
+                tournament.register(player_id.clone())?;
+                let event = TournamentEvent::PrizePoolChanged(tournament.prize_pool_status());
+                let _ = self.broadcaster.send(ServerEvent::Tournament(event));
+                if banked {
+                    self.entry_fees.insert(player_id.clone(), stack);
+                }
+                tournament.config.starting_stack
+            }
+            None => {
+                if banked {
+                    self.banked.insert(player_id.clone(), stack);
+                }
+                stack
+            }
+        };
+
+        let player = PublicPlayer {
+            id: player_id.clone(),
+            seat,
+            stack,
+            status: PlayerStatus::Waiting,
+            committed: 0,
+            cards_drawn: vec![],
+        };
+
+        if seat < self.state.players.len() {
+            self.state.players[seat] = player;
+        } else {
+            self.state.players.push(player);
+        }
+        // A bought-in cash stack was journaled by the account store along with the bankroll debit.
+        match &self.tournament {
+            Some(_) => self.journal(JournalKind::Issue, LedgerAccount::TournamentChips, self.stack_account(seat), stack),
+            None if !banked => self.journal(JournalKind::Issue, LedgerAccount::GuestChips, self.stack_account(seat), stack),
+            None => {}
+        }
+
+        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+
+        let idle = !self.hand_in_progress();
+        match &mut self.tournament {
+            Some(tournament) if tournament.ready_to_start() => {
+                tournament.start(self.now);
+                self.entry_fees.clear();
+                self.start_new_round();
+            }
+            Some(tournament) if tournament.is_running() && idle => self.start_new_round(),
+            Some(_) => {}
+            None if matches!(self.state.phase, RoundPhase::Waiting) => self.start_new_round(),
+            None => {}
+        }
+
+        Ok(player_id)
+    }
+
+    fn player_chips(&self, player_id: &str) -> Option<u64> {
+        let player = self.state.players.iter().find(|p| p.id == player_id && p.status != PlayerStatus::SittingOut)?;
+        Some(player.stack + self.pending_chips.get(player_id).copied().unwrap_or(0))
+    }
+
+    fn purchase(&mut self, player_id: &str, purchase: Purchase) -> Result<(), String> {
+        let Some(tournament) = &mut self.tournament else {
+            return Err("no tournament at this table".into());
+        };
+        let Some(seat) = self.state.players.iter().position(|p| p.id == player_id) else {
+            return Err("unknown player".into());
+        };
+
+        let stack = self.state.players[seat].stack + self.pending_chips.get(player_id).copied().unwrap_or(0);
+        let chips = match purchase {
+            Purchase::Rebuy if stack == 0 => return Err("busted players re-enter instead".into()),
+            Purchase::Rebuy => tournament.rebuy(player_id, stack)?,
+            Purchase::AddOn => tournament.add_on(player_id)?,
+            Purchase::ReEntry => tournament.reenter(player_id)?,
+        };
+
+        let event = TournamentEvent::PrizePoolChanged(tournament.prize_pool_status());
+        let _ = self.broadcaster.send(ServerEvent::Tournament(event));
+
+        if let Purchase::ReEntry = purchase {
+            self.state.players[seat].status = PlayerStatus::Waiting;
+        }
+        self.add_chips(player_id, chips);
+        Ok(())
+    }
+
+    fn handle_deal(&mut self, player_id: &str, action: DealAction) -> Result<(), String> {
+        let Some(tournament) = &mut self.tournament else {
+            return Err("no tournament at this table".into());
+        };
+
+        match action {
+            DealAction::Propose { method } => {
+                tournament.request_deal(player_id, method)?;
+                self.open_deal();
+            }
+            DealAction::Accept => {
+                let agreed = tournament.accept_deal(player_id)?;
+                let event = TournamentEvent::DealAccepted { player_id: player_id.to_string() };
+                let _ = self.broadcaster.send(ServerEvent::Tournament(event));
+                if agreed {
+                    self.finish_tournament();
+                }
+            }
+            DealAction::Reject => {
+                tournament.reject_deal(player_id)?;
+                let event = TournamentEvent::DealRejected { player_id: player_id.to_string() };
+                let _ = self.broadcaster.send(ServerEvent::Tournament(event));
+                if !self.hand_in_progress() {
+                    self.start_new_round();
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// A deal asked for mid-hand is priced once the hand is over.
+    fn open_deal(&mut self) {
+        if self.hand_in_progress() { return; }
+        let Some(tournament) = &mut self.tournament else { return; };
+
+        let stacks: Vec<(String, u64)> = self.state.players
+            .iter()
+            .filter(|p| p.status != PlayerStatus::SittingOut && p.stack > 0)
+            .map(|p| (p.id.clone(), p.stack))
+            .collect();
+        if let Some(proposal) = tournament.open_deal(&stacks) {
+            let _ = self.broadcaster.send(ServerEvent::Tournament(TournamentEvent::DealProposed(proposal)));
+        }
+    }
+
+    /// Chips bought during a hand wait until it is over.
+    fn add_chips(&mut self, player_id: &str, amount: u64) {
+        *self.pending_chips.entry(player_id.to_string()).or_default() += amount;
+
+        if !self.hand_in_progress() {
+            self.apply_pending_chips();
+            let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+        }
+    }
+
+    fn apply_pending_chips(&mut self) {
+        for seat in 0..self.state.players.len() {
+            if let Some(chips) = self.pending_chips.remove(&self.state.players[seat].id) {
+                self.journal(JournalKind::Issue, LedgerAccount::TournamentChips, self.stack_account(seat), chips);
+                self.state.players[seat].stack += chips;
+            }
+        }
+    }
+
+    /// Seats a moved or newly assigned player. Vacant seats are filled starting right after the
+    /// current big blind so the newcomer neither skips nor double-posts the blinds. Not refused
+    /// while draining: the director stops new entries itself, and a player it has already
+    /// released from another table must land somewhere.
+    fn seat_player(&mut self, player_id: String, stack: u64, away: bool) -> Result<usize, String> {
+        if self.state.players.iter().any(|p| p.id == player_id && p.status != PlayerStatus::SittingOut) {
+            return Err("player already seated".into());
+        }
+
+        // A re-entering player takes back their old seat.
+        let seats = self.state.players.len();
+        let vacant = self.state.players.iter().position(|p| p.id == player_id).or_else(|| {
+            (1..=seats)
+                .map(|offset| (self.big_blind_seat + offset) % seats)
+                .find(|&seat| self.state.players[seat].status == PlayerStatus::SittingOut)
+        });
+        if vacant.is_none() && seats >= MAX_PLAYERS {
+            return Err("Table full".into());
+        }
+
+        let seat = vacant.unwrap_or(seats);
+        let player = PublicPlayer {
+            id: player_id.clone(),
+            seat,
+            stack,
+            status: PlayerStatus::Waiting,
+            committed: 0,
+            cards_drawn: vec![],
+        };
+
+        if seat < seats {
+            self.state.players[seat] = player;
+        } else {
+            self.state.players.push(player);
+        }
+        self.journal(JournalKind::Transfer, LedgerAccount::TournamentChips, self.stack_account(seat), stack);
+        if away {
+            self.away.insert(player_id);
+        }
+
+        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+        Ok(seat)
+    }
+
+    /// Frees the seat of the player due to post the next big blind, for balancing between hands.
+    fn release_player(&mut self) -> Result<ReleasedPlayer, String> {
+        if self.hand_in_progress() {
+            return Err("hand in progress".into());
+        }
+
+        let seats = self.state.players.len();
+        let seat = (1..=seats)
+            .map(|offset| (self.big_blind_seat + offset) % seats)
+            .find(|&seat| self.state.players[seat].status != PlayerStatus::SittingOut)
+            .ok_or("no players seated")?;
+
+        self.journal(JournalKind::Transfer, self.stack_account(seat), LedgerAccount::TournamentChips, self.state.players[seat].stack);
+        let player = &mut self.state.players[seat];
+        let stack = std::mem::take(&mut player.stack);
+        player.status = PlayerStatus::SittingOut;
+        let player_id = player.id.clone();
+        let away = self.away.remove(&player_id);
+
+        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+        Ok(ReleasedPlayer { player_id, stack, away })
+    }
+
+    fn handle_action(&mut self, player_id: &str, action: PlayerAction) -> Result<(), String> {
+        if self.paused {
+            return Err("the table is paused".into());
+        }
+        let seat = self.state.players.iter().position(|p| p.id == player_id).ok_or("player not found")?;
+        if self.state.current_turn_seat != Some(seat) {
+            return Err("not your turn".into());
+        }
+
+        let phase = self.state.phase.clone();
+        let mut recorded = RecordedAction::from(&action);
+        let stack_before = self.state.players[seat].stack;
+
+        match (phase.clone(), action) {
+            (RoundPhase::Draw { .. }, PlayerAction::Draw { discard }) => {
+                recorded = RecordedAction::Draw { discarded: self.apply_draw(seat, discard)? };
+            }
+            (RoundPhase::Draw { .. }, _) => return Err("only draw actions are allowed during the draw".into()),
+            (_, PlayerAction::Draw { .. }) => return Err("not a draw phase".into()),
+            (RoundPhase::RunoutVote, PlayerAction::RunBoards { boards }) => self.record_runout_vote(seat, boards)?,
+            (RoundPhase::RunoutVote, _) => return Err("choose how many boards to run".into()),
+            (_, PlayerAction::RunBoards { .. }) => return Err("no runout to vote on".into()),
+            (phase, action) if phase.is_betting() => self.apply_bet_action(seat, action)?,
+            _ => return Err("no hand in progress".into()),
+        }
+
+        let amount = stack_before - self.state.players[seat].stack;
+        self.record_action(phase, seat, recorded, amount);
+        self.acted.insert(player_id.to_string());
+        self.advance();
+        Ok(())
+    }
+
+    fn apply_bet_action(&mut self, seat: usize, action: PlayerAction) -> Result<(), String> {
+        let player = &self.state.players[seat];
+        let to_call = self.state.current_bet.saturating_sub(player.committed);
+
+        match action {
+            PlayerAction::Fold => {
+                self.state.players[seat].status = PlayerStatus::Folded;
+            }
+            PlayerAction::Check => {
+                if to_call > 0 {
+                    return Err("cannot check facing a bet".into());
+                }
+            }
+            PlayerAction::Call => {
+                if to_call == 0 {
+                    return Err("nothing to call".into());
+                }
+                self.commit(seat, to_call, JournalKind::Bet);
+            }
+            PlayerAction::Bet { amount } => {
+                if self.state.current_bet > 0 {
+                    return Err("cannot bet, there is already a bet".into());
+                }
+                self.raise_by(seat, amount)?;
+            }
+            PlayerAction::Raise { amount } => {
+                if self.state.current_bet == 0 {
+                    return Err("nothing to raise".into());
+                }
+                self.raise_by(seat, amount)?;
+            }
+            PlayerAction::AllIn => {
+                if player.stack == 0 {
+                    return Err("no chips left".into());
+                }
+                self.raise_by(seat, player.stack)?;
+            }
+            PlayerAction::Draw { .. } | PlayerAction::RunBoards { .. } => {
+                return Err("not a betting action".into());
+            }
+        }
+
+        Ok(())
+    }
+
+    fn raise_by(&mut self, seat: usize, amount: u64) -> Result<(), String> {
+        let player = &self.state.players[seat];
+        let amount = amount.min(player.stack);
+        let all_in = amount == player.stack;
+        let to_call = self.state.current_bet.saturating_sub(player.committed);
+        let new_total = player.committed + amount;
+        let raise_size = new_total.saturating_sub(self.state.current_bet);
+
+        match self.state.betting_structure {
+            BettingStructure::NoLimit => {}
+            BettingStructure::PotLimit => {
+                let max_raise = self.state.pot + to_call;
+                if raise_size > max_raise {
+                    return Err(format!("maximum raise is {max_raise}"));
+                }
+            }
+            BettingStructure::FixedLimit => {
+                let bet_size = self.limit_bet_size();
+                if raise_size > 0 && self.bets_this_round >= FIXED_LIMIT_BET_CAP {
+                    return Err("betting is capped this round".into());
+                }
+                if raise_size > bet_size || (!all_in && raise_size != bet_size) {
+                    return Err(format!("bets and raises are fixed at {bet_size}"));
+                }
+            }
+        }
+
+        if !all_in && raise_size < self.min_raise.max(1) {
+            return Err(format!("minimum raise is {}", self.min_raise));
+        }
+
+        self.commit(seat, amount, JournalKind::Bet);
+
+        if raise_size > 0 {
+            self.min_raise = self.min_raise.max(raise_size);
+            self.state.current_bet = new_total;
+            self.bets_this_round += 1;
+            self.acted.clear();
+        }
+
+        Ok(())
+    }
+
+    fn limit_bet_size(&self) -> u64 {
+        let big_bet_street = match self.state.phase {
+            RoundPhase::Turn | RoundPhase::River => true,
+            RoundPhase::PostDraw { round } => round * 2 > self.state.variant.draw_rounds(),
+            _ => false,
+        };
+
+        if big_bet_street {
+            self.state.big_blind_amount * 2
+        } else {
+            self.state.big_blind_amount
+        }
+    }
+
+    fn commit(&mut self, seat: usize, amount: u64, kind: JournalKind) -> u64 {
+        let paid = amount.min(self.state.players[seat].stack);
+        self.journal(kind, self.stack_account(seat), self.pot_account(), paid);
+
+        let player = &mut self.state.players[seat];
+
+        player.stack -= paid;
+        player.committed += paid;
+        self.state.pot += paid;
+        *self.contributions.entry(player.id.clone()).or_default() += paid;
+
+        if player.stack == 0 {
+            player.status = PlayerStatus::AllIn;
+        }
+
+        paid
+    }
+
+    /// Returns how many cards were actually swapped once repeated indices are dropped.
+    fn apply_draw(&mut self, seat: usize, discard: Vec<usize>) -> Result<usize, String> {
+        let player_id = self.state.players[seat].id.clone();
+        let hand_len = self.cards.hands.get(&player_id).map(Vec::len).ok_or("no cards dealt")?;
+
+        let mut discard = discard;
+        discard.sort_unstable();
+        discard.dedup();
+
+        if discard.iter().any(|&i| i >= hand_len) {
+            return Err("invalid discard index".into());
+        }
+        if self.cards.deck.len() + self.cards.discards.len() < discard.len() {
+            return Err("not enough cards left to draw".into());
+        }
+
+        let replacements: Vec<Card> = discard.iter().map(|_| self.draw_from_stub()).collect();
+        let hand = self.cards.hands.get_mut(&player_id).ok_or("no cards dealt")?;
+        for (&i, card) in discard.iter().zip(replacements) {
+            let old = std::mem::replace(&mut hand[i], card);
+            self.cards.discards.push(old);
+        }
+
+        self.state.players[seat].cards_drawn.push(discard.len());
+        Ok(discard.len())
+    }
+
+    fn record_runout_vote(&mut self, seat: usize, boards: u8) -> Result<(), String> {
+        if boards == 0 || boards > self.max_boards {
+            return Err(format!("boards must be between 1 and {}", self.max_boards));
+        }
+
+        self.runout_votes.insert(self.state.players[seat].id.clone(), boards);
+        Ok(())
+    }
+
+    fn should_offer_runouts(&self) -> bool {
+        let can_bet = self.state.players.iter().filter(|p| p.status == PlayerStatus::Active).count();
+        let in_hand = self.state.players.iter().filter(|p| Self::in_hand(p)).count();
+
+        self.max_boards > 1 && self.state.variant.uses_board() && can_bet < 2 && in_hand >= 2
+    }
+
+    fn run_out_boards(&mut self, boards: u8) {
+        let common = self.state.community_cards.clone();
+
+        let runouts: Vec<Vec<Card>> = (0..boards.max(1))
+            .map(|_| {
+                let mut board = common.clone();
+                while board.len() < BOARD_CARDS {
+                    self.burn();
+                    let street = if board.is_empty() { FLOP_CARDS } else { 1 };
+                    board.extend((0..street).filter_map(|_| self.cards.deck.pop()));
+                }
+                board
+            })
+            .collect();
+
+        self.state.community_cards = runouts[0].clone();
+        if runouts.len() > 1 {
+            self.state.boards = runouts;
+        }
+        self.state.phase = RoundPhase::River;
+    }
+
+    fn draw_from_stub(&mut self) -> Card {
+        if self.cards.deck.is_empty() {
+            self.cards.deck.append(&mut self.cards.discards);
+            self.cards.reshuffles += 1;
+            match &self.cards.shuffle {
+                Some(proof) => fairness::shuffle(&mut self.cards.deck, &fairness::reshuffle_seed(proof, self.cards.reshuffles)),
+                None => self.cards.deck.shuffle(&mut self.rng),
+            }
+        }
+        self.cards.deck.pop().expect("deck and discards empty when drawing")
+    }
+
+    fn advance(&mut self) {
+        let in_hand = self.state.players.iter().filter(|p| Self::in_hand(p)).count();
+        if in_hand <= 1 {
+            self.finish_hand();
+            return;
+        }
+
+        let from = self.state.current_turn_seat.unwrap_or(self.state.dealer_seat);
+        match self.next_to_act(from) {
+            Some(seat) => self.set_turn(seat),
+            None => self.next_street(),
+        }
+    }
+
+    fn set_turn(&mut self, seat: usize) {
+        self.state.current_turn_seat = Some(seat);
+        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+    }
+
+    /// Tournament players who disconnected keep their seat and are checked or folded in turn, one
+    /// action per clock tick so a table of absent players cannot play itself in a loop.
+    fn act_if_away(&mut self) {
+        let Some(seat) = self.state.current_turn_seat else { return; };
+        let player = &self.state.players[seat];
+        if !self.away.contains(&player.id) { return; }
+
+        let player_id = player.id.clone();
+        let action = match self.state.phase {
+            RoundPhase::Draw { .. } => PlayerAction::Draw { discard: vec![] },
+            RoundPhase::RunoutVote => PlayerAction::RunBoards { boards: 1 },
+            _ if player.committed >= self.state.current_bet => PlayerAction::Check,
+            _ => PlayerAction::Fold,
+        };
+
+        for action in [action, PlayerAction::Fold] {
+            if self.handle_action(&player_id, action.clone()).is_ok() {
+                self.record(LoggedCommand::Action { player_id, action });
+                return;
+            }
+        }
+    }
+
+    fn next_to_act(&self, from: usize) -> Option<usize> {
+        let players = &self.state.players;
+        if players.is_empty() { return None; }
+
+        let every_player_acts = matches!(self.state.phase, RoundPhase::Draw { .. } | RoundPhase::RunoutVote);
+        if !every_player_acts {
+            let can_bet: Vec<&PublicPlayer> = players.iter().filter(|p| p.status == PlayerStatus::Active).collect();
+            if can_bet.len() < 2 && can_bet.iter().all(|p| p.committed >= self.state.current_bet) {
+                return None;
+            }
+        }
+
+        (1..=players.len())
+            .map(|offset| (from + offset) % players.len())
+            .find(|&seat| {
+                let p = &players[seat];
+                if every_player_acts {
+                    Self::in_hand(p) && !self.acted.contains(&p.id)
+                } else {
+                    p.status == PlayerStatus::Active
+                        && (!self.acted.contains(&p.id) || p.committed < self.state.current_bet)
+                }
+            })
+    }
+
+    fn next_street(&mut self) {
+        for p in &mut self.state.players {
+            p.committed = 0;
+        }
+        self.state.current_bet = 0;
+        self.state.current_turn_seat = None;
+        self.min_raise = self.state.big_blind_amount;
+        self.bets_this_round = 0;
+        self.acted.clear();
+
+        let draw_rounds = self.state.variant.draw_rounds();
+        match self.state.phase.clone() {
+            RoundPhase::Preflop | RoundPhase::Flop | RoundPhase::Turn if self.should_offer_runouts() => {
+                self.runout_votes.clear();
+                self.state.phase = RoundPhase::RunoutVote;
+            }
+            RoundPhase::RunoutVote => {
+                let boards = self.runout_votes.values().min().copied().unwrap_or(1);
+                self.run_out_boards(boards);
+                self.finish_hand();
+                return;
+            }
+            RoundPhase::Preflop => self.deal_flop(),
+            RoundPhase::Flop => self.deal_turn(),
+            RoundPhase::Turn => self.deal_river(),
+            RoundPhase::Predraw => self.state.phase = RoundPhase::Draw { round: 1 },
+            RoundPhase::Draw { round } => self.state.phase = RoundPhase::PostDraw { round },
+            RoundPhase::PostDraw { round } if round < draw_rounds => {
+                self.state.phase = RoundPhase::Draw { round: round + 1 };
+            }
+            RoundPhase::River | RoundPhase::PostDraw { .. } => {
+                self.finish_hand();
+                return;
+            }
+            RoundPhase::Waiting | RoundPhase::Showdown => return,
+        }
+
+        match self.next_to_act(self.state.dealer_seat) {
+            Some(seat) => self.set_turn(seat),
+            None => self.next_street(),
+        }
+    }
+
+    fn finish_hand(&mut self) {
+        let saw_flop = !matches!(self.state.phase, RoundPhase::Preflop | RoundPhase::Predraw);
+        self.state.current_turn_seat = None;
+        self.state.phase = RoundPhase::Showdown;
+
+        let live: HashMap<String, usize> = self.state.players
+            .iter()
+            .filter(|p| Self::in_hand(p))
+            .map(|p| (p.id.clone(), p.seat))
+            .collect();
+
+        let mut contributions: Vec<(String, u64, bool)> = self.contributions
+            .iter()
+            .map(|(id, amount)| (id.clone(), *amount, live.contains_key(id)))
+            .collect();
+        self.return_uncalled(&mut contributions);
+        let mut pots = build_pots(&contributions);
+        let rake = self.rake(&pots, saw_flop);
+        take_rake(&mut pots, rake);
+        self.journal(JournalKind::Rake, self.pot_account(), LedgerAccount::Rake, rake);
+
+        let boards = if self.state.boards.is_empty() {
+            vec![self.state.community_cards.clone()]
+        } else {
+            self.state.boards.clone()
+        };
+
+        let mut revealed = Vec::new();
+        let mut scores = vec![HashMap::new(); boards.len()];
+        if live.len() > 1 {
+            for (board_index, board) in boards.iter().enumerate() {
+                for (id, seat) in &live {
+                    let hand = self.cards.hands.get(id).cloned().unwrap_or_default();
+                    let (score, description) = evaluate(self.state.variant, &hand, board);
+                    scores[board_index].insert(id.clone(), score);
+                    revealed.push(RevealedHand { seat: *seat, board: board_index, hand, description });
+                }
+            }
+            revealed.sort_by_key(|r| (r.board, r.seat));
+        }
+
+        let seats = self.state.players.len();
+        let dealer = self.state.dealer_seat;
+        let mut awards = Vec::new();
+
+        for (pot_index, pot) in pots.iter().enumerate().filter(|(_, pot)| pot.amount > 0) {
+            // Each board plays for an equal share of every pot; odd chips go to the first board.
+            let portions = boards.len() as u64;
+
+            for (board, scores) in scores.iter().enumerate() {
+                let portion = pot.amount / portions + u64::from((board as u64) < pot.amount % portions);
+
+                let best = pot.eligible.iter().map(|id| scores.get(id)).max().flatten();
+                let mut winners: Vec<usize> = pot.eligible
+                    .iter()
+                    .filter(|id| scores.get(*id) == best)
+                    .filter_map(|id| live.get(id).copied())
+                    .collect();
+                // Nobody left in the hand can win these chips; take them off the table on the record.
+                if winners.is_empty() {
+                    tracing::warn!(table_id = %self.table_id, pot_index, portion, "pot has no eligible winner; writing it off");
+                    self.journal(JournalKind::WriteOff, self.pot_account(), LedgerAccount::WriteOff, portion);
+                    continue;
+                }
+
+                // Odd chips go to the winners closest to the left of the button.
+                winners.sort_by_key(|seat| (seat + seats - dealer - 1) % seats);
+
+                let share = portion / winners.len() as u64;
+                let remainder = portion % winners.len() as u64;
+                for (i, seat) in winners.into_iter().enumerate() {
+                    let amount = share + u64::from((i as u64) < remainder);
+                    self.journal(JournalKind::PotAward, self.pot_account(), self.stack_account(seat), amount);
+                    self.state.players[seat].stack += amount;
+                    awards.push(PotAward { pot_index, board, seat, amount });
+                }
+            }
+        }
+
+        self.state.pot = 0;
+        let hand_id = self.hand_log.as_ref().map(|log| log.hand_id.clone());
+        self.write_hand_log(boards, revealed.clone(), awards.clone(), rake);
+        let _ = self.broadcaster.send(ServerEvent::Showdown { hands: revealed, awards, rake });
+        self.reveal_shuffle(hand_id);
+
+        let busted: Vec<(String, u64)> = self.state.players
+            .iter()
+            .filter(|p| p.stack == 0 && p.status != PlayerStatus::SittingOut)
+            .map(|p| (p.id.clone(), self.contributions.get(&p.id).copied().unwrap_or(0)))
+            .collect();
+        self.record_eliminations(busted.clone());
+        if let Some(clock) = self.tournament.as_mut().and_then(Tournament::clock_mut) {
+            clock.record_hand();
+        }
+
+        for p in &mut self.state.players {
+            p.committed = 0;
+            if p.stack == 0 {
+                p.status = PlayerStatus::SittingOut;
+            }
+        }
+
+        if self.tournament.as_ref().is_some_and(|t| t.remaining() <= 1) {
+            self.finish_tournament();
+            return;
+        }
+
+        if let Some((table_id, reports)) = &self.reports {
+            let seated = self.state.players
+                .iter()
+                .filter(|p| p.status != PlayerStatus::SittingOut)
+                .map(|p| p.id.clone())
+                .collect();
+            let _ = reports.send(HandReport { table_id: *table_id, busted, seated });
+
+            self.state.phase = RoundPhase::Waiting;
+            let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+            return;
+        }
 
-pub struct GameManager {
-    pub state: PublicGameState,
-    pub cards: CardStore,
-    pub broadcaster: broadcast::Sender<ServerEvent>,
-    rng: StdRng,
-}
+        if let Some(spec) = self.rotation.record_hand(self.cards.hands.len()).cloned() {
+            self.apply_variant(&spec);
+        }
 
-impl GameManager {
-    pub fn new() -> Self {
-        let (tx, _) = broadcast::channel(128);
-        Self {
-            state: PublicGameState {
-                players: vec![],
-                community_cards: vec![],
-                pot: 0,
-                dealer_seat: 0,
-                current_turn_seat: None,
-                phase: RoundPhase::Waiting,
-                small_blind_amount: 10,
-                big_blind_amount: 20,
-                current_bet: 0,
-            },
-            cards: CardStore::new(),
-            broadcaster: tx,
-            rng: StdRng::from_entropy(),
+        self.start_new_round();
+    }
+
+    /// Cash games only; nothing is taken from a hand that ends before the flop under
+    /// "no flop, no drop".
+    fn rake(&self, pots: &[Pot], saw_flop: bool) -> u64 {
+        let Some(config) = &self.rotation.config().rake else { return 0; };
+        if self.tournament.is_some() || self.reports.is_some() || (config.no_flop_no_drop && !saw_flop) {
+            return 0;
         }
+        let total = pots.iter().map(|pot| pot.amount).sum();
+        rake(config, total, self.cards.hands.len())
     }
 
-    pub fn start() -> (mpsc::Sender<GameCommand>, broadcast::Sender<ServerEvent>) {
-        let (tx_cmd, rx_cmd) = mpsc::channel::<GameCommand>(COMMAND_CHANNEL_CAPACITY);
-        let mut manager = GameManager::new();
-        let broadcaster = manager.broadcaster.clone();
-        
-        tokio::spawn(async move { manager.run(rx_cmd).await; });
-        
-        (tx_cmd, broadcaster)
+    /// Hands the top bettor whatever nobody else matched, so it is neither raked nor awarded.
+    fn return_uncalled(&mut self, contributions: &mut [(String, u64, bool)]) {
+        let Some(top) = (0..contributions.len()).max_by_key(|&i| contributions[i].1) else { return; };
+        let called = contributions.iter().enumerate().filter(|(i, _)| *i != top).map(|(_, c)| c.1).max().unwrap_or(0);
+        let uncalled = contributions[top].1 - called;
+        let Some(seat) = self.state.players.iter().position(|p| p.id == contributions[top].0) else { return; };
+        if uncalled == 0 || !contributions[top].2 {
+            return;
+        }
+
+        contributions[top].1 = called;
+        self.state.pot -= uncalled;
+        self.state.players[seat].stack += uncalled;
+        self.journal(JournalKind::Uncalled, self.pot_account(), self.stack_account(seat), uncalled);
     }
 
-    async fn run(&mut self, mut rx: mpsc::Receiver<GameCommand>) {
-        while let Some(cmd) = rx.recv().await {
-            match cmd {
-                GameCommand::Join { reply } => {
-                    let res = self.add_waiting_player();
-                    let _ = reply.send(res);
-                }
-                GameCommand::Action { player_id, action, reply } => {
-                    let res = self.handle_action(&player_id, action);
-                    let _ = reply.send(res);
-                }
-                GameCommand::Disconnect { player_id } => {
-                    self.handle_disconnect(&player_id);
-                }
-                GameCommand::GetPrivateState { player_id, reply } => {
-                    let private = self.private_state(&player_id);
-                    let _ = reply.send(private);
-                }
+    fn record_eliminations(&mut self, busted: Vec<(String, u64)>) {
+        let Some(tournament) = &mut self.tournament else { return; };
+
+        for (player_id, position) in tournament.eliminate(busted) {
+            let event = TournamentEvent::PlayerEliminated { player_id, position };
+            let _ = self.broadcaster.send(ServerEvent::Tournament(event));
+        }
+    }
+
+    fn finish_tournament(&mut self) {
+        let Some(tournament) = &mut self.tournament else { return; };
+        let results = tournament.finish();
+        if let Some(recorder) = &self.recorder {
+            for result in results.iter().filter(|r| r.prize > 0) {
+                let prize = CashOut { account_id: result.player_id.clone(), table_id: self.table_id.clone(), amount: result.prize, bought: 0 };
+                let _ = recorder.ledger.send(LedgerRecord::Prize(prize));
             }
         }
-        tracing::info!("GameManager actor exiting (command channel closed)");
+
+        self.state.phase = RoundPhase::Waiting;
+        self.state.current_turn_seat = None;
+        let _ = self.broadcaster.send(ServerEvent::Tournament(TournamentEvent::Finished { results }));
+        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
     }
 
-    fn add_waiting_player(&mut self) -> Result<String, String> {
-        if self.state.players.len() >= MAX_PLAYERS {
-            return Err("Table full".into());
+    fn update_blind_level(&mut self) {
+        let Some(tournament) = &mut self.tournament else { return; };
+        if let Some(clock) = tournament.clock_mut() {
+            clock.tick(self.now);
         }
+        let Some((level, blinds)) = tournament.take_level_change() else { return; };
 
-        let player_id = Uuid::new_v4().to_string();
-        let seat = self.state.players.len();
+        self.apply_blinds(&blinds);
+        let event = TournamentEvent::LevelChanged { level, blinds };
+        let _ = self.broadcaster.send(ServerEvent::Tournament(event));
+    }
 
-        self.state.players.push(PublicPlayer {
-            id: player_id.clone(),
-            seat,
-            stack: 1000,
-            status: PlayerStatus::Waiting,
-            committed: 0,
-        });
+    fn apply_blinds(&mut self, blinds: &BlindLevel) {
+        self.state.small_blind_amount = blinds.small_blind;
+        self.state.big_blind_amount = blinds.big_blind;
+        self.state.ante_amount = blinds.ante;
+    }
 
-        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+    /// Advances a sit-and-go's clock, broadcasts the countdown and resumes dealing after a break.
+    /// Returns whether the clock moved to a new step.
+    fn on_clock_tick(&mut self) -> bool {
+        let Some(tournament) = &mut self.tournament else { return false; };
+        if !tournament.is_running() { return false; }
+        let Some(clock) = tournament.clock_mut() else { return false; };
 
-        if matches!(self.state.phase, RoundPhase::Waiting) {
-            let active_count = self.state.players.iter().filter(|p| p.status == PlayerStatus::Active).count();
-            let waiting_count = self.state.players.iter().filter(|p| p.status == PlayerStatus::Waiting).count();
-            
-            if active_count + waiting_count >= 2 {
-                for p in &mut self.state.players {
-                    if p.status == PlayerStatus::Waiting {
-                        p.status = PlayerStatus::Active;
-                    }
-                }
-                self.start_new_round();
-            }
+        let now = self.now;
+        let moved = clock.tick(now);
+        self.clock_ticks += 1;
+        if moved || self.clock_ticks.is_multiple_of(CLOCK_BROADCAST_TICKS) {
+            let event = TournamentEvent::Clock(clock.status(now));
+            let _ = self.broadcaster.send(ServerEvent::Tournament(event));
         }
 
-        Ok(player_id)
+        if moved && !clock.on_break() && !self.hand_in_progress() {
+            self.start_new_round();
+        }
+        moved
     }
 
-    fn handle_action(&mut self, player_id: &str, action: PlayerAction) -> Result<(), String> {
-        let p = self.state.players.iter_mut().find(|p| p.id == player_id).ok_or("player not found")?;
-        match action {
-            PlayerAction::Fold => {
-                p.status = PlayerStatus::Waiting;
-            }
-            PlayerAction::Check | PlayerAction::Call => {}
-            PlayerAction::Bet { amount } | PlayerAction::Raise { amount } => {
-                let a = amount.min(p.stack);
-                p.stack = p.stack.saturating_sub(a);
-                p.committed += a;
-                self.state.pot += a;
-            }
-            PlayerAction::AllIn => {
-                let a = p.stack;
-                p.stack = 0;
-                p.committed += a;
-                self.state.pot += a;
-            }
+    fn apply_variant(&mut self, spec: &VariantSpec) {
+        self.state.variant = spec.variant;
+        self.state.betting_structure = spec.structure;
+        if self.tournament.is_none() {
+            self.state.small_blind_amount = spec.small_blind;
+            self.state.big_blind_amount = spec.big_blind;
         }
 
+        let _ = self.broadcaster.send(ServerEvent::VariantChanged(spec.clone()));
+    }
+
+    fn in_hand(player: &PublicPlayer) -> bool {
+        matches!(player.status, PlayerStatus::Active | PlayerStatus::AllIn)
+    }
+
+    fn hand_in_progress(&self) -> bool {
+        !matches!(self.state.phase, RoundPhase::Waiting | RoundPhase::Showdown)
+    }
+
+    fn restart(&mut self) {
+        let seated = self.state.players.iter().filter(|p| p.status != PlayerStatus::SittingOut);
+        self.away.extend(seated.map(|p| p.id.clone()));
+    }
+
+    fn reconnect(&mut self, player_id: &str) -> Result<bool, String> {
+        if !self.state.players.iter().any(|p| p.id == player_id && p.status != PlayerStatus::SittingOut) {
+            return Ok(false);
+        }
+        if !self.away.remove(player_id) {
+            return Err("already seated at this table".into());
+        }
         let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
-        Ok(())
+        Ok(true)
+    }
+
+    /// Cash players still away when a hand is due leave the table.
+    fn stand_up_away_players(&mut self) {
+        if self.tournament.is_some() || self.reports.is_some() { return; }
+
+        for seat in 0..self.state.players.len() {
+            if self.away.remove(&self.state.players[seat].id) {
+                self.state.players[seat].status = PlayerStatus::SittingOut;
+                self.cash_out(seat);
+            }
+        }
     }
 
     fn handle_disconnect(&mut self, player_id: &str) {
-        if let Some(p) = self.state.players.iter_mut().find(|p| p.id == player_id) {
-            p.status = PlayerStatus::Waiting;
+        let Some(seat) = self.state.players.iter().position(|p| p.id == player_id) else {
+            return;
+        };
+
+        if self.reports.is_some() || self.tournament.as_ref().is_some_and(Tournament::is_running) {
+            self.away.insert(player_id.to_string());
+            return;
+        }
+
+        self.state.players[seat].status = PlayerStatus::SittingOut;
+        self.cash_out(seat);
+
+        if self.hand_in_progress() {
+            let in_hand = self.state.players.iter().filter(|p| Self::in_hand(p)).count();
+            if in_hand <= 1 || self.state.current_turn_seat == Some(seat) {
+                self.advance();
+                return;
+            }
         }
+
         let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
     }
 
+    /// Takes a departing player's chips off the table: an account's go back to its bankroll, a
+    /// guest's are retired. Tournament chips are always retired; a sit-and-go entrant leaving
+    /// before the start is unregistered and gets the entry fee back.
+    fn cash_out(&mut self, seat: usize) {
+        if let Some(tournament) = &mut self.tournament {
+            let player_id = self.state.players[seat].id.clone();
+            if tournament.unregister(&player_id) {
+                let event = TournamentEvent::PrizePoolChanged(tournament.prize_pool_status());
+                let _ = self.broadcaster.send(ServerEvent::Tournament(event));
+            }
+            self.journal(JournalKind::Retire, self.stack_account(seat), LedgerAccount::TournamentChips, self.state.players[seat].stack);
+            self.state.players[seat].stack = 0;
+            if let (Some(fee), Some(recorder)) = (self.entry_fees.remove(&player_id), &self.recorder) {
+                let refund = CashOut { account_id: player_id, table_id: self.table_id.clone(), amount: fee, bought: 0 };
+                let _ = recorder.ledger.send(LedgerRecord::Refund(refund));
+            }
+            return;
+        }
+
+        let Some(bought) = self.banked.remove(&self.state.players[seat].id) else {
+            self.journal(JournalKind::Retire, self.stack_account(seat), LedgerAccount::GuestChips, self.state.players[seat].stack);
+            self.state.players[seat].stack = 0;
+            return;
+        };
+
+        let player = &mut self.state.players[seat];
+        let amount = std::mem::take(&mut player.stack);
+        if let Some(recorder) = &self.recorder {
+            let cash_out = CashOut { account_id: player.id.clone(), table_id: self.table_id.clone(), amount, bought };
+            let _ = recorder.ledger.send(LedgerRecord::CashOut(cash_out));
+        }
+    }
+
+    fn stack_account(&self, seat: usize) -> LedgerAccount {
+        LedgerAccount::Stack { table_id: self.table_id.clone(), player_id: self.state.players[seat].id.clone() }
+    }
+
+    fn pot_account(&self) -> LedgerAccount {
+        LedgerAccount::Pot { table_id: self.table_id.clone() }
+    }
+
+    fn journal(&self, kind: JournalKind, from: LedgerAccount, to: LedgerAccount, amount: u64) {
+        self.journal_with_memo(kind, from, to, amount, None);
+    }
+
+    fn journal_with_memo(&self, kind: JournalKind, from: LedgerAccount, to: LedgerAccount, amount: u64, memo: Option<String>) {
+        let Some(recorder) = &self.recorder else { return; };
+        if amount == 0 { return; }
+
+        let hand_id = self.hand_log.as_ref().map(|log| log.hand_id.clone());
+        let mut entry = JournalEntry::transfer(&self.table_id, hand_id, kind, from, to, amount);
+        entry.memo = memo;
+        let _ = recorder.ledger.send(LedgerRecord::Journal(entry));
+    }
+
     fn private_state(&self, player_id: &str) -> PrivateState {
         let hand = self.cards.hands.get(player_id).cloned();
         PrivateState { hand }
@@ -146,43 +1799,74 @@ impl GameManager {
 
     fn masked_state(&self) -> PublicGameState {
         PublicGameState {
+            variant: self.state.variant,
+            betting_structure: self.state.betting_structure,
             players: self.state.players.clone(),
             community_cards: self.state.community_cards.clone(),
+            boards: self.state.boards.clone(),
             pot: self.state.pot,
             dealer_seat: self.state.dealer_seat,
             current_turn_seat: self.state.current_turn_seat,
             phase: self.state.phase.clone(),
             small_blind_amount: self.state.small_blind_amount,
             big_blind_amount: self.state.big_blind_amount,
+            ante_amount: self.state.ante_amount,
             current_bet: self.state.current_bet,
+            next_shuffle_commitment: self.state.next_shuffle_commitment.clone(),
         }
     }
 
     fn init_deck(&mut self) {
-        let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
-        let ranks = [
-            Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six,
-            Rank::Seven, Rank::Eight, Rank::Nine, Rank::Ten,
-            Rank::Jack, Rank::Queen, Rank::King, Rank::Ace,
-        ];
+        self.cards.discards.clear();
+        self.cards.reshuffles = 0;
 
-        self.cards.deck.clear();
-        for suit in &suits {
-            for rank in &ranks {
-                self.cards.deck.push(Card { suit: *suit, rank: *rank });
-            }
+        if let Some(stacked) = self.stacked_decks.pop_front() {
+            let mut rest: Vec<Card> = fairness::fresh_deck().into_iter().filter(|card| !stacked.contains(card)).collect();
+            rest.shuffle(&mut self.rng);
+            rest.extend(stacked.into_iter().rev());
+            self.cards.deck = rest;
+            self.cards.shuffle = None;
+            return;
         }
 
-        self.cards.deck.shuffle(&mut self.rng);
+        let dealt_in: Vec<String> = self.state.players
+            .iter()
+            .filter(|p| p.status == PlayerStatus::Active)
+            .map(|p| p.id.clone())
+            .collect();
+        let proof = self.shuffle.deal(&dealt_in, &mut self.rng);
+        self.state.next_shuffle_commitment = self.shuffle.next_commitment();
+
+        self.cards.deck = proof.deck.clone();
+        self.cards.shuffle = Some(proof);
+    }
+
+    /// Publishes the server seed behind the hand just finished or called off; the deck stays with
+    /// the hand history, where only the players dealt in can read it.
+    fn reveal_shuffle(&mut self, hand_id: Option<String>) {
+        let Some(proof) = self.cards.shuffle.take() else { return; };
+        let _ = self.broadcaster.send(ServerEvent::ShuffleRevealed {
+            hand_number: self.hand_number,
+            hand_id,
+            commitment: proof.commitment,
+            server_seed: proof.server_seed,
+        });
     }
 
     fn reset_round_state(&mut self) {
         self.init_deck();
         self.state.community_cards.clear();
+        self.state.boards.clear();
+        self.runout_votes.clear();
         self.state.pot = 0;
+        self.state.current_bet = 0;
+        self.min_raise = self.state.big_blind_amount;
+        self.contributions.clear();
+        self.acted.clear();
 
         for p in &mut self.state.players {
             p.committed = 0;
+            p.cards_drawn.clear();
         }
 
         self.cards.hands.clear();
@@ -191,20 +1875,24 @@ impl GameManager {
     fn deal_hole_cards(&mut self) {
         let active_ids: Vec<String> = self.state.players
             .iter()
-            .filter(|p| p.status == PlayerStatus::Active)
+            .filter(|p| Self::in_hand(p))
             .map(|p| p.id.clone())
             .collect();
 
+        let hole_cards = self.state.variant.hole_cards();
         for pid in active_ids {
-            let c1 = self.cards.deck.pop().expect("deck empty when dealing hole cards");
-            let c2 = self.cards.deck.pop().expect("deck empty when dealing hole cards");
+            let hand = (0..hole_cards)
+                .map(|_| self.cards.deck.pop().expect("deck empty when dealing hole cards"))
+                .collect();
             
-            self.cards.hands.insert(pid, [c1, c2]);
+            self.cards.hands.insert(pid, hand);
         }
     }
 
     fn burn(&mut self) {
-        let _ = self.cards.deck.pop();
+        if let Some(c) = self.cards.deck.pop() {
+            self.cards.discards.push(c);
+        }
     }
 
     fn deal_flop(&mut self) {
@@ -215,7 +1903,6 @@ impl GameManager {
             }
         }
         self.state.phase = RoundPhase::Flop;
-        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
     }
 
     fn deal_turn(&mut self) {
@@ -224,7 +1911,6 @@ impl GameManager {
             self.state.community_cards.push(c);
         }
         self.state.phase = RoundPhase::Turn;
-        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
     }
 
     fn deal_river(&mut self) {
@@ -233,88 +1919,651 @@ impl GameManager {
             self.state.community_cards.push(c);
         }
         self.state.phase = RoundPhase::River;
-        let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
     }
 
     fn move_dealer(&mut self) {
         if self.state.players.is_empty() { return; }
-        self.state.dealer_seat = (self.state.dealer_seat + 1) % self.state.players.len();
+        self.state.dealer_seat = self.next_active_seat(self.state.dealer_seat);
     }
 
-    fn post_blinds(&mut self) {
-        if self.state.players.len() < 2 { return; }
+    fn post_blinds(&mut self) -> usize {
+        let active = self.state.players.iter().filter(|p| p.status == PlayerStatus::Active).count();
 
-        let small_blind_seat = self.next_active_seat(self.state.dealer_seat);
+        // Heads-up the button posts the small blind.
+        let small_blind_seat = if active == 2 {
+            self.state.dealer_seat
+        } else {
+            self.next_active_seat(self.state.dealer_seat)
+        };
         let big_blind_seat = self.next_active_seat(small_blind_seat);
 
-        self.apply_blind(small_blind_seat, self.state.small_blind_amount);
-        self.apply_blind(big_blind_seat, self.state.big_blind_amount);
+        self.post_antes();
+        self.apply_blind(small_blind_seat, self.state.small_blind_amount, RecordedAction::SmallBlind);
+        self.apply_blind(big_blind_seat, self.state.big_blind_amount, RecordedAction::BigBlind);
 
         self.state.current_bet = self.state.big_blind_amount;
+        self.bets_this_round = 1;
+        self.big_blind_seat = big_blind_seat;
+        big_blind_seat
     }
 
-    fn apply_blind(&mut self, seat: usize, amount: u64) {
+    fn apply_blind(&mut self, seat: usize, amount: u64, kind: RecordedAction) {
         if seat >= self.state.players.len() { return; }
 
-        let player = &mut self.state.players[seat];
-        let blind = amount.min(player.stack);
+        let blind = self.commit(seat, amount, JournalKind::Blind);
+        self.record_action(self.state.phase.clone(), seat, kind, blind);
+
+        let _ = self.broadcaster.send(ServerEvent::BlindPosted { seat, amount: blind });
+    }
 
-        player.stack = player.stack.saturating_sub(blind);
+    /// Antes go into the pot without counting towards anyone's bet for the street.
+    fn post_antes(&mut self) {
+        let ante = self.state.ante_amount;
+        if ante == 0 { return; }
 
-        player.committed += blind;
-        self.state.pot += blind;
+        for seat in 0..self.state.players.len() {
+            if self.state.players[seat].status != PlayerStatus::Active { continue; }
 
-        if player.stack == 0 {
-            player.status = PlayerStatus::AllIn;
+            let paid = self.commit(seat, ante, JournalKind::Ante);
+            self.state.players[seat].committed -= paid;
+            self.record_action(self.state.phase.clone(), seat, RecordedAction::Ante, paid);
+            let _ = self.broadcaster.send(ServerEvent::AntePosted { seat, amount: paid });
         }
+    }
 
-        let _ = self.broadcaster.send(ServerEvent::BlindPosted { seat, amount: blind });
+    fn begin_hand_log(&mut self) {
+        let now = Utc::now();
+        if let Some(recorder) = &self.recorder {
+            recorder.metrics.hands_dealt.inc();
+        }
+        let seats = self.state.players
+            .iter()
+            .filter(|p| p.status == PlayerStatus::Active)
+            .map(|p| SeatRecord { seat: p.seat, player_id: p.id.clone(), stack: p.stack, cards: vec![] })
+            .collect();
+
+        self.hand_log = Some(HandHistory {
+            hand_id: Uuid::new_v4().to_string(),
+            table_id: self.table_id.clone(),
+            hand_number: self.hand_number,
+            tournament: self.tournament.is_some() || self.reports.is_some(),
+            started_at: now,
+            ended_at: now,
+            variant: self.state.variant,
+            betting_structure: self.state.betting_structure,
+            small_blind: self.state.small_blind_amount,
+            big_blind: self.state.big_blind_amount,
+            ante: self.state.ante_amount,
+            button_seat: self.state.dealer_seat,
+            seats,
+            actions: vec![],
+            boards: vec![],
+            showdown: vec![],
+            winners: vec![],
+            rake: 0,
+            shuffle: None,
+        });
     }
 
-    fn next_active_seat(&self, from: usize) -> usize {
-        if self.state.players.is_empty() { return 0; }
-        
-        let mut seat = from;
+    fn record_action(&mut self, phase: RoundPhase, seat: usize, action: RecordedAction, amount: u64) {
+        if let Some(recorder) = &self.recorder {
+            recorder.metrics.actions.with_label_values(&[action.as_str()]).inc();
+        }
+        let Some(log) = &mut self.hand_log else { return; };
+        let player = &self.state.players[seat];
 
-        loop {
-            seat = (seat + 1) % self.state.players.len();
-            if self.state.players[seat].status == PlayerStatus::Active {
-                return seat;
-            }
+        log.actions.push(ActionRecord {
+            phase,
+            seat,
+            player_id: player.id.clone(),
+            action,
+            amount,
+            total_bet: player.committed,
+        });
+    }
+
+    fn write_hand_log(&mut self, boards: Vec<Vec<Card>>, showdown: Vec<RevealedHand>, winners: Vec<PotAward>, rake: u64) {
+        let Some(mut log) = self.hand_log.take() else { return; };
+
+        for seat in &mut log.seats {
+            seat.cards = self.cards.hands.get(&seat.player_id).cloned().unwrap_or_default();
+        }
+        log.ended_at = Utc::now();
+        log.boards = boards;
+        log.showdown = showdown;
+        log.winners = winners;
+        log.rake = rake;
+        log.shuffle = self.cards.shuffle.clone();
+
+        if let Some(recorder) = &self.recorder {
+            let duration = (log.ended_at - log.started_at).to_std().unwrap_or_default();
+            recorder.metrics.hand_duration.observe(duration.as_secs_f64());
+            let _ = recorder.histories.send(log);
         }
     }
 
-    fn start_new_round(&mut self) {
-        let active_players_count = self.state.players
-            .iter()
-            .filter(|p| p.status == PlayerStatus::Active)
-            .count();
+    fn next_active_seat(&self, from: usize) -> usize {
+        let players = &self.state.players;
+        if players.is_empty() { return 0; }
 
-        let waiting_players_count = self.state.players
-            .iter()
-            .filter(|p| p.status == PlayerStatus::Waiting)
-            .count();
+        (1..=players.len())
+            .map(|offset| (from + offset) % players.len())
+            .find(|&seat| players[seat].status == PlayerStatus::Active)
+            .unwrap_or(from)
+    }
+
+    fn start_new_round(&mut self) {
+        self.stand_up_away_players();
+        self.apply_pending_chips();
+        let eligible = |p: &PublicPlayer| p.status != PlayerStatus::SittingOut && p.stack > 0;
 
-        if active_players_count + waiting_players_count < 2 {
+        if self.draining || self.paused || self.state.players.iter().filter(|p| eligible(p)).count() < 2 {
+            self.state.phase = RoundPhase::Waiting;
+            self.state.current_turn_seat = None;
+            let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
             return;
         }
 
         for p in &mut self.state.players {
-            if p.status == PlayerStatus::Waiting {
+            if eligible(p) {
                 p.status = PlayerStatus::Active;
             }
         }
 
+        self.update_blind_level();
+        if let Some(blinds) = self.pending_blinds.take() {
+            self.apply_blinds(&blinds);
+        }
+        if self.tournament.as_ref().is_some_and(|t| t.on_break() || t.deal_pending()) {
+            self.state.phase = RoundPhase::Waiting;
+            self.state.current_turn_seat = None;
+            let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
+            self.open_deal();
+            return;
+        }
         self.reset_round_state();
+        self.hand_number += 1;
         self.move_dealer();
-        self.post_blinds();
+        self.state.phase = if self.state.variant.uses_board() {
+            RoundPhase::Preflop
+        } else {
+            RoundPhase::Predraw
+        };
+        self.begin_hand_log();
+        let big_blind_seat = self.post_blinds();
         self.deal_hole_cards();
 
-        self.state.phase = RoundPhase::Preflop;
-        self.state.current_turn_seat = Some(self.next_active_seat(self.state.dealer_seat));
+        self.state.current_turn_seat = self.next_to_act(big_blind_seat);
 
         let _ = self.broadcaster.send(ServerEvent::GameState(self.masked_state()));
         let _ = self.broadcaster.send(ServerEvent::RoundStarted);
+
+        if self.state.current_turn_seat.is_none() {
+            self.next_street();
+        }
+    }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{GameVariant, RakeCap, RakeConfig, parse_cards};
+    use crate::infrastructure::{AccountStore, Flush, Metrics};
+    use crate::shared::STARTING_BANKROLL;
+
+    fn holdem() -> GameManager {
+        GameManager::seeded(TableConfig::single(GameVariant::Holdem), 7)
+    }
+
+    fn sit_and_go() -> GameManager {
+        let mut config = TableConfig::single(GameVariant::Holdem);
+        config.sit_and_go = Some(serde_json::from_value(serde_json::json!({
+            "entrants": 2, "buy_in": 100, "starting_stack": 1000, "payouts": [100],
+        })).unwrap());
+        GameManager::seeded(config, 7)
+    }
+
+    /// Checks and calls, or moves all in, until the current hand is over and returns its awards.
+    fn play_out(table: &mut GameManager, events: &mut broadcast::Receiver<ServerEvent>, all_in: bool) -> Vec<PotAward> {
+        let hand = table.hand_number;
+        while table.hand_number == hand {
+            let Some(seat) = table.state.current_turn_seat else { break };
+            let player = &table.state.players[seat];
+            let id = player.id.clone();
+            let action = match table.state.phase {
+                RoundPhase::RunoutVote => PlayerAction::RunBoards { boards: 1 },
+                _ if all_in => PlayerAction::AllIn,
+                _ if player.committed < table.state.current_bet => PlayerAction::Call,
+                _ => PlayerAction::Check,
+            };
+            table.handle_action(&id, action).unwrap();
+        }
+
+        std::iter::from_fn(|| events.try_recv().ok())
+            .find_map(|event| match event {
+                ServerEvent::Showdown { awards, .. } => Some(awards),
+                _ => None,
+            })
+            .expect("the hand reached a showdown")
+    }
+
+    #[test]
+    fn repeated_discards_are_recorded_once() {
+        let mut table = GameManager::seeded(TableConfig::single(GameVariant::FiveCardDraw), 7);
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        let mut act = |action| {
+            let seat = table.state.current_turn_seat.unwrap();
+            let id = table.state.players[seat].id.clone();
+            table.handle_action(&id, action).unwrap();
+        };
+        act(PlayerAction::Call);
+        act(PlayerAction::Check);
+        act(PlayerAction::Draw { discard: vec![3, 3, 3] });
+
+        let drawn = table.hand_log.as_ref().unwrap().actions.last().unwrap();
+        assert!(matches!(drawn.action, RecordedAction::Draw { discarded: 1 }));
+    }
+
+    #[test]
+    fn stacked_deck_deals_in_order() {
+        let mut table = holdem();
+        table.stack_deck(parse_cards("Ah Kh 2c 7d 5s Qh Jh Th 6s 3c 8s 4d")).unwrap();
+        let mut events = table.broadcaster.subscribe();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+
+        assert_eq!(table.cards.hands["a"], parse_cards("Ah Kh"));
+        assert_eq!(table.cards.hands["b"], parse_cards("2c 7d"));
+        assert!(table.cards.shuffle.is_none());
+
+        let awards = play_out(&mut table, &mut events, false);
+        assert_eq!(awards.len(), 1);
+        assert_eq!(awards[0].seat, 0);
+    }
+
+    #[test]
+    fn board_plays_for_a_split_pot() {
+        let mut table = holdem();
+        table.stack_deck(parse_cards("2c 3d 4c 5d 9s Ah Kh Qh 9c Jh 9d Th")).unwrap();
+        let mut events = table.broadcaster.subscribe();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+
+        let awards = play_out(&mut table, &mut events, false);
+        assert_eq!(awards.len(), 2);
+        assert_eq!(awards[0].amount, awards[1].amount);
+    }
+
+    #[test]
+    fn short_stack_wins_only_the_main_pot() {
+        let mut table = holdem();
+        table.stack_deck(vec![]).unwrap();
+        table.stack_deck(parse_cards("Kh Kd 2c 7d Ah Ad 5c 3s 8c 9h 6c Jd 6d 4s")).unwrap();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        table.add_waiting_player("c".into(), 100, false).unwrap();
+
+        // The first hand is heads-up; the short stack is dealt in from the second.
+        let seat = table.state.current_turn_seat.unwrap();
+        let id = table.state.players[seat].id.clone();
+        table.handle_action(&id, PlayerAction::Fold).unwrap();
+        let mut events = table.broadcaster.subscribe();
+        assert_eq!(table.cards.hands["c"], parse_cards("Ah Ad"));
+
+        let awards = play_out(&mut table, &mut events, true);
+        let won_by = |seat| awards.iter().filter(|a| a.seat == seat).map(|a| a.amount).sum::<u64>();
+        assert_eq!(won_by(2), 300);
+        assert!(awards.iter().any(|a| a.pot_index == 1 && a.seat == 0));
+        assert_eq!(won_by(1), 0);
+    }
+
+    #[test]
+    fn all_in_runs_one_board_unless_the_table_offers_more() {
+        let mut table = holdem();
+        let mut events = table.broadcaster.subscribe();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        let all_in = |table: &mut GameManager| {
+            let seat = table.state.current_turn_seat.unwrap();
+            let id = table.state.players[seat].id.clone();
+            table.handle_action(&id, PlayerAction::AllIn).unwrap();
+        };
+        all_in(&mut table);
+        all_in(&mut table);
+        assert!(std::iter::from_fn(|| events.try_recv().ok()).any(|event| matches!(event, ServerEvent::Showdown { .. })));
+
+        let mut config = TableConfig::single(GameVariant::Holdem);
+        config.max_boards = 2;
+        let mut table = GameManager::seeded(config, 7);
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        all_in(&mut table);
+        all_in(&mut table);
+        assert!(matches!(table.state.phase, RoundPhase::RunoutVote));
+    }
+
+    #[test]
+    fn the_shuffle_reveal_opens_the_published_commitment() {
+        let mut table = holdem();
+        let mut events = table.broadcaster.subscribe();
+        let published = table.state.next_shuffle_commitment.clone();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        play_out(&mut table, &mut events, false);
+
+        let (hand_id, commitment) = std::iter::from_fn(|| events.try_recv().ok())
+            .find_map(|event| match event {
+                ServerEvent::ShuffleRevealed { hand_id, commitment, .. } => Some((hand_id, commitment)),
+                _ => None,
+            })
+            .expect("the shuffle was revealed");
+        assert!(hand_id.is_some());
+        assert_eq!(commitment, published);
+    }
+
+    fn raked(caps: Vec<RakeCap>) -> GameManager {
+        let mut config = TableConfig::single(GameVariant::Holdem);
+        config.rake = Some(RakeConfig { basis_points: 500, caps, no_flop_no_drop: true });
+        let mut table = GameManager::seeded(config, 7);
+        table.stack_deck(parse_cards("Ah Kh 2c 7d 5s Qh Jh Th 6s 3c 8s 4d")).unwrap();
+        table.stack_deck(parse_cards("Ah Kh 2c 7d 5s Qh Jh Th 6s 3c 8s 4d")).unwrap();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        table
+    }
+
+    #[test]
+    fn rake_is_a_share_of_the_pot_up_to_the_cap() {
+        for (caps, rake) in [(vec![], 2), (vec![RakeCap { players: 2, cap: 1 }], 1)] {
+            let mut table = raked(caps);
+            let mut events = table.broadcaster.subscribe();
+            let awards = play_out(&mut table, &mut events, false);
+            assert_eq!(awards[0].amount, 40 - rake);
+        }
+    }
+
+    #[test]
+    fn rake_caps_follow_the_players_dealt_in() {
+        let mut config = RakeConfig { basis_points: 500, caps: vec![], no_flop_no_drop: true };
+        assert_eq!(rake(&config, 1000, 9), 50);
+
+        config.caps = vec![RakeCap { players: 3, cap: 2 }, RakeCap { players: 5, cap: 4 }];
+        assert_eq!(rake(&config, 1000, 2), 2);
+        assert_eq!(rake(&config, 1000, 4), 2);
+        assert_eq!(rake(&config, 1000, 5), 4);
+        assert_eq!(rake(&config, 1000, 9), 4);
+    }
+
+    #[test]
+    fn rake_leaves_uncalled_chips_alone() {
+        let mut config = TableConfig::single(GameVariant::Holdem);
+        config.rake = Some(RakeConfig { basis_points: 500, caps: vec![], no_flop_no_drop: false });
+        let mut table = GameManager::seeded(config, 7);
+        table.stack_deck(parse_cards("Kh Kd 2c 7d Ah Ad 5c 3s 8c 9h 6c Jd 6d 4s")).unwrap();
+        table.pause().unwrap();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 500, false).unwrap();
+        table.add_waiting_player("c".into(), 100, false).unwrap();
+        let mut events = table.broadcaster.subscribe();
+        table.resume().unwrap();
+
+        let awards = play_out(&mut table, &mut events, true);
+        // 5% of the 300 main pot and the 800 side pot; a's last 500 went uncalled and came back.
+        assert_eq!(awards.iter().map(|a| a.amount).sum::<u64>(), 1100 - 55);
+        let chips: u64 = table.state.players.iter().map(|p| p.stack).sum::<u64>() + table.state.pot;
+        assert_eq!(chips, 1600 - 55);
+    }
+
+    #[test]
+    fn pots_won_by_a_bet_after_the_flop_are_raked() {
+        let mut table = raked(vec![]);
+        let mut events = table.broadcaster.subscribe();
+        let mut act = |action| {
+            let seat = table.state.current_turn_seat.unwrap();
+            let id = table.state.players[seat].id.clone();
+            table.handle_action(&id, action).unwrap();
+        };
+        act(PlayerAction::Call);
+        act(PlayerAction::Check);
+        act(PlayerAction::Bet { amount: 100 });
+        act(PlayerAction::Fold);
+
+        // The bet comes back uncalled and the 40 left in the pot is raked.
+        let awards = std::iter::from_fn(|| events.try_recv().ok())
+            .find_map(|event| match event {
+                ServerEvent::Showdown { awards, .. } => Some(awards),
+                _ => None,
+            })
+            .unwrap();
+        assert_eq!(awards[0].amount, 38);
+        assert_eq!(table.state.players.iter().map(|p| p.stack).sum::<u64>() + table.state.pot, 2000 - 2);
+    }
+
+    #[test]
+    fn no_flop_no_drop() {
+        let mut table = raked(vec![]);
+        let mut events = table.broadcaster.subscribe();
+        let seat = table.state.current_turn_seat.unwrap();
+        let id = table.state.players[seat].id.clone();
+        table.handle_action(&id, PlayerAction::Fold).unwrap();
+
+        let rakes: Vec<u64> = std::iter::from_fn(|| events.try_recv().ok())
+            .filter_map(|event| match event {
+                ServerEvent::Showdown { rake, .. } => Some(rake),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rakes, vec![0]);
+
+        let awards = play_out(&mut table, &mut events, false);
+        assert_eq!(awards[0].amount, 38);
+    }
+
+    #[test]
+    fn draining_finishes_the_hand_without_dealing_another() {
+        let mut table = holdem();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        let mut events = table.broadcaster.subscribe();
+        let mut notices = table.broadcaster.subscribe();
+
+        let (reply, mut drained) = oneshot::channel();
+        table.handle_command(GameCommand::Drain { reply });
+        assert!(matches!(notices.try_recv(), Ok(ServerEvent::ServerShuttingDown)));
+        assert!(table.add_waiting_player("c".into(), 1000, false).is_err());
+
+        play_out(&mut table, &mut events, false);
+        assert_eq!(table.hand_number, 1);
+        assert!(matches!(table.state.phase, RoundPhase::Waiting));
+        assert!(drained.try_recv().is_err());
+
+        table.finish_drain();
+        assert!(drained.try_recv().is_ok());
+    }
+
+    #[test]
+    fn away_players_act_once_per_tick() {
+        let mut table = sit_and_go();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        table.away.extend(["a".to_string(), "b".to_string()]);
+
+        // The small blind folds, and the next hand waits for the next tick.
+        table.act_if_away();
+        assert_eq!(table.hand_number, 2);
+        table.act_if_away();
+        assert_eq!(table.hand_number, 3);
+    }
+
+    #[test]
+    fn players_keep_their_seats_after_a_restart_until_the_next_hand() {
+        let mut table = holdem();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+
+        table.restart();
+        assert!(table.hand_in_progress());
+        assert_eq!(table.reconnect("a"), Ok(true));
+        assert!(table.reconnect("a").is_err());
+        assert_eq!(table.reconnect("c"), Ok(false));
+
+        while table.hand_in_progress() {
+            let seat = table.state.current_turn_seat.unwrap();
+            if table.state.players[seat].id != "a" {
+                table.act_if_away();
+            } else if table.handle_action("a", PlayerAction::Check).is_err() {
+                table.handle_action("a", PlayerAction::Call).unwrap();
+            }
+        }
+        assert!(table.state.players[1].status == PlayerStatus::SittingOut);
+        assert!(matches!(table.state.phase, RoundPhase::Waiting));
     }
 
+    #[test]
+    fn voiding_a_paused_hand_gives_every_chip_back() {
+        let mut table = holdem();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        let seat = table.state.current_turn_seat.unwrap();
+        let id = table.state.players[seat].id.clone();
+        table.handle_action(&id, PlayerAction::Call).unwrap();
+
+        table.handle_command(GameCommand::Pause { reply: oneshot::channel().0 });
+        let seat = table.state.current_turn_seat.unwrap();
+        let id = table.state.players[seat].id.clone();
+        assert!(table.handle_action(&id, PlayerAction::Check).is_err());
+
+        let (reply, mut voided) = oneshot::channel();
+        table.handle_command(GameCommand::VoidHand { reply });
+        assert!(voided.try_recv().unwrap().is_ok());
+        assert!(table.state.players.iter().all(|p| p.stack == 1000));
+        assert_eq!(table.state.pot, 0);
+        assert!(matches!(table.state.phase, RoundPhase::Waiting));
+
+        table.handle_command(GameCommand::Resume { reply: oneshot::channel().0 });
+        assert_eq!(table.hand_number, 2);
+        assert!(matches!(table.state.phase, RoundPhase::Preflop));
+    }
+
+    #[test]
+    fn banned_players_cannot_sit_again() {
+        let mut table = holdem();
+        for id in ["a", "b", "c"] {
+            table.add_waiting_player(id.into(), 1000, false).unwrap();
+        }
+
+        table.remove_player("b", false).unwrap();
+        table.remove_player("c", true).unwrap();
+        assert!(table.state.players.iter().filter(|p| p.id != "a").all(|p| p.status == PlayerStatus::SittingOut));
+        assert!(table.remove_player("b", false).is_err());
+
+        assert!(table.add_waiting_player("b".into(), 1000, false).is_ok());
+        assert!(table.add_waiting_player("c".into(), 1000, false).is_err());
+    }
+
+    #[test]
+    fn closing_waits_for_the_hand_then_stands_everyone_up() {
+        let mut table = holdem();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        assert!(table.close().is_err());
+
+        table.remove_player("b", false).unwrap();
+        let mut events = table.broadcaster.subscribe();
+        table.close().unwrap();
+        assert!(table.closed);
+        assert!(table.state.players.iter().all(|p| matches!(p.status, PlayerStatus::SittingOut) && p.stack == 0));
+        assert!(std::iter::from_fn(|| events.try_recv().ok()).any(|event| matches!(event, ServerEvent::TableClosed)));
+    }
+
+    #[test]
+    fn stacks_are_adjusted_between_hands_with_a_reason() {
+        let mut table = holdem();
+        table.add_waiting_player("a".into(), 1000, false).unwrap();
+        assert!(table.adjust_stack("a", 500, " ", "op").is_err());
+        assert!(table.adjust_stack("a", -1001, "refund", "op").is_err());
+        assert_eq!(table.adjust_stack("a", -200, "misclick refund", "op").unwrap(), 800);
+
+        table.add_waiting_player("b".into(), 1000, false).unwrap();
+        assert!(table.adjust_stack("a", 200, "misclick refund", "op").is_err());
+
+        let mut tournament = sit_and_go();
+        tournament.add_waiting_player("a".into(), 0, false).unwrap();
+        assert!(tournament.adjust_stack("a", 500, "bonus", "op").is_err());
+    }
+
+    #[test]
+    fn seeded_tables_deal_the_same_cards() {
+        let deal = |seed| {
+            let mut table = GameManager::seeded(TableConfig::single(GameVariant::Holdem), seed);
+            table.add_waiting_player("a".into(), 1000, false).unwrap();
+            table.add_waiting_player("b".into(), 1000, false).unwrap();
+            table.cards.deck.clone()
+        };
+
+        assert_eq!(deal(1), deal(1));
+        assert_ne!(deal(1), deal(2));
+    }
+
+    #[test]
+    fn sit_and_go_entries_are_never_banked_and_unregister_before_the_start() {
+        let mut table = sit_and_go();
+        table.add_waiting_player("a".into(), 100, true).unwrap();
+        assert!(table.banked.is_empty());
+        assert_eq!(table.entry_fees.get("a"), Some(&100));
+        assert_eq!(table.state.players[0].stack, 1000);
+
+        table.handle_disconnect("a");
+        assert!(table.entry_fees.is_empty());
+        assert_eq!(table.state.players[0].stack, 0);
+        assert_eq!(table.tournament.as_ref().unwrap().prize_pool_status().entries, 0);
+
+        table.add_waiting_player("a".into(), 100, true).unwrap();
+        table.add_waiting_player("b".into(), 100, true).unwrap();
+        assert_eq!(table.state.players.len(), 2);
+        assert!(table.tournament.as_ref().unwrap().is_running());
+        assert!(table.entry_fees.is_empty());
+    }
+
+    #[tokio::test]
+    async fn sit_and_go_prizes_are_paid_from_the_prize_pool() {
+        let accounts = AccountStore::open(":memory:").unwrap();
+        let (flush_writer, flush) = Flush::new();
+        let (ledger, writer) = accounts.start_ledger_writer(flush);
+        let mut players = vec![];
+        for name in ["alice", "bob"] {
+            let id = accounts.register(name, "password").await.unwrap().id;
+            accounts.enter_tournament(&id, "sng", 100).await.unwrap();
+            players.push(id);
+        }
+
+        let mut table = sit_and_go();
+        table.table_id = "sng".into();
+        table.recorder = Some(TableRecorder {
+            histories: mpsc::unbounded_channel().0,
+            log: mpsc::unbounded_channel().0,
+            snapshots: mpsc::unbounded_channel().0,
+            ledger,
+            metrics: Metrics::default(),
+        });
+        let mut events = table.broadcaster.subscribe();
+        for id in &players {
+            table.add_waiting_player(id.clone(), 100, true).unwrap();
+        }
+        while table.tournament.as_ref().unwrap().is_running() {
+            play_out(&mut table, &mut events, true);
+        }
+        let winner = table.state.players.iter().find(|p| p.stack > 0).unwrap().id.clone();
+
+        drop(table);
+        let _ = flush_writer.send(true);
+        writer.await.unwrap();
+        for id in &players {
+            let bankroll = accounts.account(id).await.unwrap().unwrap().bankroll;
+            let expected = if *id == winner { STARTING_BANKROLL + 100 } else { STARTING_BANKROLL - 100 };
+            assert_eq!(bankroll, expected);
+        }
+    }
 }
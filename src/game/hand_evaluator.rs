@@ -0,0 +1,206 @@
+use crate::domain::{Card, GameVariant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HandCategory {
+    HighCard,
+    OnePair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HandValue {
+    pub category: HandCategory,
+    pub kickers: [u8; 5],
+}
+
+impl HandValue {
+    fn label(&self) -> String {
+        self.kickers.iter().map(|k| rank_label(*k)).collect::<Vec<_>>().join("-")
+    }
+
+    fn encode(&self) -> u32 {
+        self.kickers
+            .iter()
+            .fold(self.category as u32, |acc, k| (acc << 4) | *k as u32)
+    }
+}
+
+/// Showdown strength comparable across players of the same variant; the higher score wins.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HandScore(u32);
+
+pub fn evaluate(variant: GameVariant, hole: &[Card], board: &[Card]) -> (HandScore, String) {
+    if variant.is_lowball() {
+        // 2-7 lowball: aces are always high, straights and flushes count against the hand.
+        let value = best_five(hole, false);
+        let description = match value.category {
+            HandCategory::HighCard => format!("{} low", value.label()),
+            category => format!("{category:?} {}", value.label()),
+        };
+        return (HandScore(u32::MAX - value.encode()), description);
+    }
+
+    let cards: Vec<Card> = hole.iter().chain(board.iter()).cloned().collect();
+    let value = best_five(&cards, true);
+    (HandScore(value.encode()), format!("{:?} {}", value.category, value.label()))
+}
+
+fn best_five(cards: &[Card], wheel_straights: bool) -> HandValue {
+    if cards.len() <= 5 {
+        return evaluate_five(cards, wheel_straights);
+    }
+
+    let n = cards.len();
+    let mut best: Option<HandValue> = None;
+    let mut combo = [0usize, 1, 2, 3, 4];
+
+    loop {
+        let hand: Vec<Card> = combo.iter().map(|&i| cards[i].clone()).collect();
+        let value = evaluate_five(&hand, wheel_straights);
+        if best.is_none_or(|b| value > b) {
+            best = Some(value);
+        }
+
+        let Some(i) = (0..5).rev().find(|&i| combo[i] < n - 5 + i) else {
+            break;
+        };
+        combo[i] += 1;
+        for j in i + 1..5 {
+            combo[j] = combo[j - 1] + 1;
+        }
+    }
+
+    best.expect("at least one five-card combination")
+}
+
+fn evaluate_five(cards: &[Card], wheel_straights: bool) -> HandValue {
+    let mut counts = [0u8; 15];
+    for c in cards {
+        counts[c.rank.value() as usize] += 1;
+    }
+
+    let mut groups: Vec<(u8, u8)> = (2..=14u8)
+        .rev()
+        .filter(|&v| counts[v as usize] > 0)
+        .map(|v| (counts[v as usize], v))
+        .collect();
+    groups.sort_by(|a, b| b.cmp(a));
+
+    let complete = cards.len() == 5;
+    let flush = complete && cards.iter().all(|c| c.suit == cards[0].suit);
+    let straight_high = if complete && groups.len() == 5 {
+        let high = groups[0].1;
+        let low = groups[4].1;
+        if high - low == 4 {
+            Some(high)
+        } else if wheel_straights && high == 14 && groups[1].1 == 5 {
+            Some(5)
+        } else {
+            None
+        }
+    } else {
+        None
+    };
+
+    let mut kickers = [0u8; 5];
+    if let Some(high) = straight_high {
+        for (i, k) in kickers.iter_mut().enumerate() {
+            *k = high.saturating_sub(i as u8).max(1);
+        }
+    } else {
+        let mut i = 0;
+        for (count, value) in &groups {
+            for _ in 0..*count {
+                if i < 5 {
+                    kickers[i] = *value;
+                    i += 1;
+                }
+            }
+        }
+    }
+
+    let category = match (straight_high.is_some(), flush, groups[0].0, groups.get(1).map(|g| g.0)) {
+        (true, true, _, _) => HandCategory::StraightFlush,
+        (_, _, 4, _) => HandCategory::FourOfAKind,
+        (_, _, 3, Some(2)) => HandCategory::FullHouse,
+        (_, true, _, _) => HandCategory::Flush,
+        (true, _, _, _) => HandCategory::Straight,
+        (_, _, 3, _) => HandCategory::ThreeOfAKind,
+        (_, _, 2, Some(2)) => HandCategory::TwoPair,
+        (_, _, 2, _) => HandCategory::OnePair,
+        _ => HandCategory::HighCard,
+    };
+
+    HandValue { category, kickers }
+}
+
+fn rank_label(value: u8) -> String {
+    match value {
+        14 | 1 => "A".into(),
+        13 => "K".into(),
+        12 => "Q".into(),
+        11 => "J".into(),
+        10 => "T".into(),
+        v => v.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::parse_cards;
+
+    fn holdem(hole: &str, board: &str) -> (HandScore, String) {
+        evaluate(GameVariant::Holdem, &parse_cards(hole), &parse_cards(board))
+    }
+
+    fn deuce_to_seven(hand: &str) -> (HandScore, String) {
+        evaluate(GameVariant::TripleDraw27, &parse_cards(hand), &[])
+    }
+
+    #[test]
+    fn kickers_break_ties_within_a_category() {
+        let (ace, description) = holdem("Ah 3c", "Ks Kd 7c 4h 2s");
+        let (queen, _) = holdem("Qh Jc", "Ks Kd 7c 4h 2s");
+        assert!(ace > queen);
+        assert_eq!(description, "OnePair K-K-A-7-4");
+
+        let (ace, _) = holdem("Ah 2c", "Qs Qd 8h 8c 5d");
+        let (king, _) = holdem("Kh 2d", "Qs Qd 8h 8c 5d");
+        assert!(ace > king);
+    }
+
+    #[test]
+    fn the_wheel_is_the_lowest_straight() {
+        let (wheel, description) = holdem("Ah 2d", "3c 4d 5h Ks Qc");
+        let (six_high, _) = holdem("6h 2c", "3c 4d 5h Ks Qc");
+        let (pair, _) = holdem("Kh 7d", "3c 4d 5h Ks Qc");
+        assert!(wheel < six_high);
+        assert!(wheel > pair);
+        assert_eq!(description, "Straight 5-4-3-2-A");
+    }
+
+    #[test]
+    fn the_board_plays_when_neither_hand_improves_it() {
+        assert_eq!(holdem("2d 3d", "Ac Kc Qc Jc Tc").0, holdem("4h 5h", "Ac Kc Qc Jc Tc").0);
+        assert_eq!(holdem("2d 3h", "As Kd 9h 7c 4s").0, holdem("2c 3s", "As Kd 9h 7c 4s").0);
+    }
+
+    #[test]
+    fn deuce_to_seven_counts_straights_flushes_and_aces_against_the_hand() {
+        let (seven_five, description) = deuce_to_seven("7c 5d 4h 3s 2c");
+        assert!(seven_five > deuce_to_seven("8c 6d 4h 3s 2d").0);
+        assert_eq!(description, "7-5-4-3-2 low");
+
+        let king_high = deuce_to_seven("Kc Qd Jh 9s 7c").0;
+        assert!(deuce_to_seven("Ac 5d 4h 3s 2c").0 < king_high);
+        assert!(deuce_to_seven("6c 5d 4h 3s 2c").0 < king_high);
+        assert!(deuce_to_seven("7c 6c 5c 4c 2c").0 < king_high);
+    }
+}
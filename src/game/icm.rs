@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+
+/// Independent Chip Model (Malmuth-Harville): the chance of finishing first is proportional
+/// to stack, and each lower place repeats the draw among the players left. `prizes[i]` pays
+/// place `i + 1`; rounding leftovers go to the biggest stacks.
+pub fn icm_equities(stacks: &[u64], prizes: &[u64]) -> Vec<u64> {
+    let mut memo = HashMap::new();
+    let all = (1u32 << stacks.len()) - 1;
+    let equities = equities_from(all, stacks, prizes, &mut memo);
+
+    let floored: Vec<u64> = equities.iter().map(|e| e.floor() as u64).collect();
+    settle(floored, stacks, prizes)
+}
+
+/// Every player is guaranteed the lowest prize still in play; the rest is split by chip count.
+pub fn chip_chop(stacks: &[u64], prizes: &[u64]) -> Vec<u64> {
+    let guaranteed = prizes.get(stacks.len().saturating_sub(1)).copied().unwrap_or(0);
+    let pool = total(stacks, prizes);
+    let remainder = pool.saturating_sub(guaranteed * stacks.len() as u64);
+    let chips: u64 = stacks.iter().sum();
+
+    let shares = stacks
+        .iter()
+        .map(|&stack| {
+            let share = match chips {
+                0 => 0,
+                _ => (remainder as u128 * stack as u128 / chips as u128) as u64,
+            };
+            guaranteed + share
+        })
+        .collect();
+    settle(shares, stacks, prizes)
+}
+
+fn total(stacks: &[u64], prizes: &[u64]) -> u64 {
+    prizes.iter().take(stacks.len()).sum()
+}
+
+fn settle(mut shares: Vec<u64>, stacks: &[u64], prizes: &[u64]) -> Vec<u64> {
+    let leftover = total(stacks, prizes).saturating_sub(shares.iter().sum());
+
+    let mut order: Vec<usize> = (0..stacks.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(stacks[i]));
+    for i in order.into_iter().cycle().take(leftover as usize) {
+        shares[i] += 1;
+    }
+    shares
+}
+
+/// Expected winnings of every player for the places still open once only `players` remain.
+fn equities_from(players: u32, stacks: &[u64], prizes: &[u64], memo: &mut HashMap<u32, Vec<f64>>) -> Vec<f64> {
+    if let Some(cached) = memo.get(&players) {
+        return cached.clone();
+    }
+
+    let mut equities = vec![0.0; stacks.len()];
+    let place = stacks.len() - players.count_ones() as usize;
+    let Some(&prize) = prizes.get(place) else {
+        return equities;
+    };
+
+    let members: Vec<usize> = (0..stacks.len()).filter(|i| players & (1 << i) != 0).collect();
+    let chips: u64 = members.iter().map(|&i| stacks[i]).sum();
+
+    for &i in &members {
+        let chance = match chips {
+            0 => 1.0 / members.len() as f64,
+            _ => stacks[i] as f64 / chips as f64,
+        };
+        equities[i] += chance * prize as f64;
+
+        let rest = equities_from(players & !(1 << i), stacks, prizes, memo);
+        for (equity, later) in equities.iter_mut().zip(rest) {
+            *equity += chance * later;
+        }
+    }
+
+    memo.insert(players, equities.clone());
+    equities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn equal_stacks_share_equally_with_the_leftover_to_the_first_seat() {
+        assert_eq!(icm_equities(&[1000, 1000, 1000], &[50, 30, 20]), [34, 33, 33]);
+        assert_eq!(icm_equities(&[1000, 1000, 1000], &[100]), [34, 33, 33]);
+    }
+
+    #[test]
+    fn icm_pays_less_than_chip_share_to_the_chip_leader() {
+        // 38.39, 32.75 and 28.86 before the two leftover chips go to the biggest stacks.
+        assert_eq!(icm_equities(&[5000, 3000, 2000], &[50, 30, 20]), [39, 33, 28]);
+        assert_eq!(icm_equities(&[3000, 1000], &[70, 30]), [60, 40]);
+    }
+
+    #[test]
+    fn winner_take_all_is_chip_share() {
+        assert_eq!(icm_equities(&[9000, 1000], &[1000]), [900, 100]);
+    }
+
+    #[test]
+    fn chip_chop_guarantees_the_lowest_prize() {
+        assert_eq!(chip_chop(&[6000, 4000], &[70, 30]), [54, 46]);
+        assert_eq!(chip_chop(&[5000, 3000, 2000], &[50, 30, 20]), [40, 32, 28]);
+    }
+}
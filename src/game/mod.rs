@@ -1,4 +1,19 @@
 pub mod game_manager;
+pub mod hand_evaluator;
+pub mod icm;
+pub mod pot;
+pub mod rake;
+pub mod rotation;
+pub mod schedule;
+pub mod tournament;
+pub mod director;
+pub mod recovery;
+pub mod registry;
+pub mod fairness;
+pub mod rng;
 mod constants;
 
-pub use  game_manager::*;
\ No newline at end of file
+pub use  game_manager::*;
+pub use director::TournamentDirector;
+pub use recovery::{RecoveryPolicy, TableRecorder, TableSnapshot};
+pub use registry::{RegisteredTable, TableRegistry};
@@ -0,0 +1,90 @@
+pub struct Pot {
+    pub amount: u64,
+    pub eligible: Vec<String>,
+}
+
+/// Splits per-hand contributions into a main pot followed by side pots.
+/// Each contribution is `(player_id, amount, still_in_hand)`.
+pub fn build_pots(contributions: &[(String, u64, bool)]) -> Vec<Pot> {
+    let mut levels: Vec<u64> = contributions
+        .iter()
+        .filter(|(_, amount, live)| *live && *amount > 0)
+        .map(|(_, amount, _)| *amount)
+        .collect();
+    levels.sort_unstable();
+    levels.dedup();
+
+    let mut pots: Vec<Pot> = Vec::new();
+    let mut floor = 0;
+
+    for level in levels {
+        let amount: u64 = contributions
+            .iter()
+            .map(|(_, c, _)| (*c).min(level).saturating_sub(floor))
+            .sum();
+        let eligible = contributions
+            .iter()
+            .filter(|(_, c, live)| *live && *c >= level)
+            .map(|(id, _, _)| id.clone())
+            .collect();
+
+        if amount > 0 {
+            pots.push(Pot { amount, eligible });
+        }
+        floor = level;
+    }
+
+    // Chips put in above the highest live contribution (e.g. by a player who later folded).
+    let dead: u64 = contributions.iter().map(|(_, c, _)| c.saturating_sub(floor)).sum();
+    if dead > 0 {
+        match pots.last_mut() {
+            Some(last) => last.amount += dead,
+            None => pots.push(Pot { amount: dead, eligible: Vec::new() }),
+        }
+    }
+
+    pots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contributions(entries: &[(&str, u64, bool)]) -> Vec<(String, u64, bool)> {
+        entries.iter().map(|&(id, amount, live)| (id.to_string(), amount, live)).collect()
+    }
+
+    #[test]
+    fn each_all_in_caps_a_side_pot() {
+        let pots = build_pots(&contributions(&[("a", 50, true), ("b", 100, true), ("c", 200, true)]));
+        assert_eq!(pots.len(), 3);
+        assert_eq!(pots[0].amount, 150);
+        assert_eq!(pots[0].eligible, ["a", "b", "c"]);
+        assert_eq!(pots[1].amount, 100);
+        assert_eq!(pots[1].eligible, ["b", "c"]);
+        assert_eq!(pots[2].amount, 100);
+        assert_eq!(pots[2].eligible, ["c"]);
+    }
+
+    #[test]
+    fn folded_chips_stay_in_the_pots_they_reached() {
+        let pots = build_pots(&contributions(&[("a", 100, true), ("b", 100, true), ("c", 60, false)]));
+        assert_eq!(pots.len(), 1);
+        assert_eq!(pots[0].amount, 260);
+        assert_eq!(pots[0].eligible, ["a", "b"]);
+
+        let pots = build_pots(&contributions(&[("a", 50, true), ("b", 200, true), ("c", 150, false)]));
+        assert_eq!(pots.len(), 2);
+        assert_eq!(pots[0].amount, 150);
+        assert_eq!(pots[1].amount, 250);
+        assert_eq!(pots[1].eligible, ["b"]);
+    }
+
+    #[test]
+    fn chips_nobody_live_can_win_make_a_pot_of_their_own() {
+        let pots = build_pots(&contributions(&[("a", 0, true), ("b", 20, false)]));
+        assert_eq!(pots.len(), 1);
+        assert_eq!(pots[0].amount, 20);
+        assert!(pots[0].eligible.is_empty());
+    }
+}
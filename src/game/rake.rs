@@ -0,0 +1,27 @@
+use crate::domain::RakeConfig;
+use crate::game::constants::BASIS_POINTS;
+use crate::game::pot::Pot;
+
+/// The rake on a pot of `total` chips from a hand dealt to `players`.
+pub fn rake(config: &RakeConfig, total: u64, players: usize) -> u64 {
+    let taken = total * u64::from(config.basis_points) / BASIS_POINTS;
+    let cap = config.caps
+        .iter()
+        .rev()
+        .find(|cap| cap.players <= players)
+        .or(config.caps.first());
+
+    match cap {
+        Some(cap) => taken.min(cap.cap),
+        None => taken,
+    }
+}
+
+/// Takes `amount` out of the pots, the main pot first.
+pub fn take_rake(pots: &mut [Pot], mut amount: u64) {
+    for pot in pots {
+        let taken = amount.min(pot.amount);
+        pot.amount -= taken;
+        amount -= taken;
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Deserialize};
+use tokio::sync::mpsc;
+
+use crate::domain::{
+    BlindLevel, Card, CardStore, GameLogEntry, HandHistory, LedgerRecord, PublicGameState, RngSeed, TableConfig,
+};
+use crate::game::fairness::ShuffleSeeds;
+use crate::game::rotation::Rotation;
+use crate::game::schedule::ClockSnapshot;
+use crate::game::tournament::Tournament;
+use crate::infrastructure::Metrics;
+
+/// Where a table sends its finished hands, its accepted commands, its snapshots and every chip
+/// movement for the ledger, and the metrics it keeps up to date.
+#[derive(Clone)]
+pub struct TableRecorder {
+    pub histories: mpsc::UnboundedSender<HandHistory>,
+    pub log: mpsc::UnboundedSender<GameLogEntry>,
+    pub snapshots: mpsc::UnboundedSender<TableSnapshot>,
+    pub ledger: mpsc::UnboundedSender<LedgerRecord>,
+    pub metrics: Metrics,
+}
+
+/// What to do with a hand that was in progress when the server went down.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryPolicy {
+    /// Call the hand off and give every player back what they put in.
+    #[default]
+    Void,
+    /// Carry on from the exact decision point.
+    Resume,
+}
+
+/// Everything needed to rebuild a table. `sequence` is the log entry the snapshot was taken at;
+/// later entries are replayed on top of it.
+#[derive(Serialize, Deserialize)]
+pub struct TableSnapshot {
+    pub table_id: String,
+    pub sequence: u64,
+    pub elapsed_ns: u64,
+    pub taken_at: DateTime<Utc>,
+    pub config: TableConfig,
+    pub seed: RngSeed,
+    pub managed: Option<usize>,
+    pub max_boards: u8,
+    pub state: PublicGameState,
+    pub cards: CardStore,
+    pub contributions: HashMap<String, u64>,
+    pub acted: HashSet<String>,
+    pub min_raise: u64,
+    pub bets_this_round: u32,
+    pub rotation: Rotation,
+    pub runout_votes: HashMap<String, u8>,
+    pub tournament: Option<Tournament>,
+    pub clock: Option<ClockSnapshot>,
+    pub away: HashSet<String>,
+    #[serde(default)]
+    pub banked: HashMap<String, u64>,
+    #[serde(default)]
+    pub entry_fees: HashMap<String, u64>,
+    #[serde(default)]
+    pub banned: HashSet<String>,
+    #[serde(default)]
+    pub paused: bool,
+    pub pending_blinds: Option<BlindLevel>,
+    pub pending_chips: HashMap<String, u64>,
+    pub big_blind_seat: usize,
+    pub hand_number: u64,
+    pub hand_log: Option<HandHistory>,
+    #[serde(default)]
+    pub shuffle: Option<ShuffleSeeds>,
+    #[serde(default)]
+    pub deterministic: bool,
+    #[serde(default)]
+    pub stacked_decks: VecDeque<Vec<Card>>,
+}
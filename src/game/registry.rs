@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use tokio::sync::{mpsc, broadcast, oneshot};
+use uuid::Uuid;
+
+use crate::domain::{
+    GameCommand, PlayerStatus, PublicGameState, RoundPhase, ServerEvent, TableConfig, TableDetails, TableSummary,
+};
+use crate::game::{GameManager, TableRecorder};
+use crate::game::constants::{MAX_PLAYERS, MAX_TABLES};
+
+#[derive(Clone)]
+pub struct RegisteredTable {
+    pub config: TableConfig,
+    pub commands: mpsc::Sender<GameCommand>,
+    pub events: broadcast::Sender<ServerEvent>,
+}
+
+/// The cash tables this server runs, by id. Tournament tables belong to their director.
+#[derive(Clone)]
+pub struct TableRegistry {
+    tables: Arc<RwLock<BTreeMap<String, RegisteredTable>>>,
+    recorder: TableRecorder,
+}
+
+impl TableRegistry {
+    pub fn new(recorder: TableRecorder) -> Self {
+        Self { tables: Arc::default(), recorder }
+    }
+
+    pub fn insert(
+        &self,
+        table_id: String,
+        config: TableConfig,
+        (commands, events): (mpsc::Sender<GameCommand>, broadcast::Sender<ServerEvent>),
+    ) {
+        let table = RegisteredTable { config, commands, events };
+        self.tables.write().expect("table registry poisoned").insert(table_id, table);
+    }
+
+    /// Starts a new table and returns its id.
+    pub fn open(&self, config: TableConfig) -> Result<String, String> {
+        config.validate()?;
+        if self.tables.read().expect("table registry poisoned").len() >= MAX_TABLES {
+            return Err(format!("no more than {MAX_TABLES} tables can run at once"));
+        }
+
+        let table_id = Uuid::new_v4().to_string();
+        let started = GameManager::start(config.clone(), table_id.clone(), self.recorder.clone());
+        self.insert(table_id.clone(), config, started);
+        tracing::info!(%table_id, "table opened");
+        Ok(table_id)
+    }
+
+    /// Forgets a table that has stopped, freeing its place under `MAX_TABLES`.
+    pub fn remove(&self, table_id: &str) {
+        self.tables.write().expect("table registry poisoned").remove(table_id);
+    }
+
+    pub fn get(&self, table_id: &str) -> Option<RegisteredTable> {
+        self.tables.read().expect("table registry poisoned").get(table_id).cloned()
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.tables.read().expect("table registry poisoned").keys().cloned().collect()
+    }
+
+    pub fn command_senders(&self) -> Vec<(String, mpsc::Sender<GameCommand>)> {
+        self.tables
+            .read()
+            .expect("table registry poisoned")
+            .iter()
+            .map(|(table_id, table)| (table_id.clone(), table.commands.clone()))
+            .collect()
+    }
+
+    /// `None` when there is no such table or it has stopped answering.
+    pub async fn details(&self, table_id: &str) -> Option<TableDetails> {
+        let table = self.get(table_id)?;
+        let (tx, rx) = oneshot::channel();
+        table.commands.send(GameCommand::GetState { reply: tx }).await.ok()?;
+        let state = rx.await.ok()?;
+
+        Some(TableDetails { summary: summarize(table_id, &table.config, &state), config: table.config, state })
+    }
+
+    pub async fn summaries(&self) -> Vec<TableSummary> {
+        let mut summaries = vec![];
+        for table_id in self.ids() {
+            if let Some(details) = self.details(&table_id).await {
+                summaries.push(details.summary);
+            }
+        }
+        summaries
+    }
+}
+
+fn summarize(table_id: &str, config: &TableConfig, state: &PublicGameState) -> TableSummary {
+    TableSummary {
+        table_id: table_id.to_string(),
+        variant: state.variant,
+        betting_structure: state.betting_structure,
+        small_blind: state.small_blind_amount,
+        big_blind: state.big_blind_amount,
+        seated: state.players.iter().filter(|p| p.status != PlayerStatus::SittingOut).count(),
+        max_players: MAX_PLAYERS,
+        mixed: config.is_mixed(),
+        sit_and_go: config.sit_and_go.is_some(),
+        hand_in_progress: state.phase != RoundPhase::Waiting,
+    }
+}
@@ -0,0 +1,27 @@
+//! The table RNG is ChaCha20, keyed with 32 bytes from the operating system and rekeyed from the
+//! operating system every `RESEED_INTERVAL_SECS` and whenever the table takes a snapshot. A table
+//! that has logged nothing since its last rekeying skips the timed one, so idle tables stay quiet.
+//! Each rekeying is written to the game log so a replay draws the same numbers. The RNG only picks
+//! server seeds; decks come from those seeds as described in `fairness`. Seeded test tables
+//! rekey from their own RNG instead, so they deal the same cards every run.
+
+use rand::{RngCore, SeedableRng};
+use rand::rngs::OsRng;
+use rand_chacha::ChaCha20Rng;
+
+use crate::domain::RngSeed;
+
+pub type TableRng = ChaCha20Rng;
+
+pub fn os_seed() -> RngSeed {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    RngSeed::Key(bytes)
+}
+
+pub fn table_rng(seed: RngSeed) -> TableRng {
+    match seed {
+        RngSeed::Key(bytes) => ChaCha20Rng::from_seed(bytes),
+        RngSeed::Number(seed) => ChaCha20Rng::seed_from_u64(seed),
+    }
+}
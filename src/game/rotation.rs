@@ -0,0 +1,49 @@
+use serde::{Serialize, Deserialize};
+
+use crate::domain::{RotationRule, TableConfig, VariantSpec};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Rotation {
+    config: TableConfig,
+    index: usize,
+    hands_played: u32,
+    orbit_length: u32,
+}
+
+impl Rotation {
+    pub fn new(mut config: TableConfig) -> Self {
+        if config.sequence.is_empty() {
+            config.sequence.push(VariantSpec::new(Default::default()));
+        }
+        Self { config, index: 0, hands_played: 0, orbit_length: 0 }
+    }
+
+    pub fn config(&self) -> &TableConfig {
+        &self.config
+    }
+
+    pub fn current(&self) -> &VariantSpec {
+        &self.config.sequence[self.index]
+    }
+
+    /// Records a finished hand and returns the next game when the rotation moves on.
+    /// An orbit is one hand per player dealt into the first hand of the current game.
+    pub fn record_hand(&mut self, players_dealt: usize) -> Option<&VariantSpec> {
+        if !self.config.is_mixed() { return None; }
+
+        if self.hands_played == 0 {
+            self.orbit_length = players_dealt as u32;
+        }
+        self.hands_played += 1;
+
+        let due = match self.config.rotation {
+            RotationRule::EveryHands { hands } => self.hands_played >= hands.max(1),
+            RotationRule::EveryOrbit => self.hands_played >= self.orbit_length.max(1),
+        };
+        if !due { return None; }
+
+        self.index = (self.index + 1) % self.config.sequence.len();
+        self.hands_played = 0;
+        Some(self.current())
+    }
+}
@@ -0,0 +1,138 @@
+use std::time::{Duration, Instant};
+use serde::{Serialize, Deserialize};
+
+use crate::domain::{BlindLevel, BlindSchedule, ClockStatus, LevelDuration, ScheduleStep};
+
+/// A clock with its running step measured as time already spent in it.
+#[derive(Serialize, Deserialize)]
+pub struct ClockSnapshot {
+    steps: Vec<ScheduleStep>,
+    index: usize,
+    step_elapsed_ns: u64,
+    hands_played: u32,
+}
+
+/// Walks a blind schedule. Levels last a number of minutes or hands, breaks a number of
+/// minutes; the final step never expires.
+#[derive(Clone)]
+pub struct ScheduleClock {
+    steps: Vec<ScheduleStep>,
+    index: usize,
+    step_started: Instant,
+    hands_played: u32,
+}
+
+impl ScheduleClock {
+    pub fn new(schedule: BlindSchedule, now: Instant) -> Self {
+        Self { steps: schedule.levels, index: 0, step_started: now, hands_played: 0 }
+    }
+
+    /// Moves past every step that has run out; returns whether the step changed.
+    pub fn tick(&mut self, now: Instant) -> bool {
+        let mut moved = false;
+
+        while self.index + 1 < self.steps.len() {
+            match self.step_length() {
+                LevelDuration::Minutes(minutes) => {
+                    let length = Duration::from_secs(minutes * 60);
+                    if now.duration_since(self.step_started) < length { break; }
+                    self.step_started += length;
+                }
+                LevelDuration::Hands(hands) => {
+                    if self.hands_played < hands { break; }
+                    self.step_started = now;
+                }
+            }
+
+            self.index += 1;
+            self.hands_played = 0;
+            moved = true;
+        }
+
+        moved
+    }
+
+    pub fn snapshot(&self, now: Instant) -> ClockSnapshot {
+        ClockSnapshot {
+            steps: self.steps.clone(),
+            index: self.index,
+            step_elapsed_ns: now.duration_since(self.step_started).as_nanos() as u64,
+            hands_played: self.hands_played,
+        }
+    }
+
+    pub fn restore(snapshot: ClockSnapshot, now: Instant) -> Self {
+        let elapsed = Duration::from_nanos(snapshot.step_elapsed_ns);
+        Self {
+            steps: snapshot.steps,
+            index: snapshot.index,
+            step_started: now.checked_sub(elapsed).unwrap_or(now),
+            hands_played: snapshot.hands_played,
+        }
+    }
+
+    pub fn record_hand(&mut self) {
+        self.hands_played += 1;
+    }
+
+    pub fn on_break(&self) -> bool {
+        matches!(self.steps.get(self.index), Some(ScheduleStep::Break { .. }))
+    }
+
+    pub fn on_first_break(&self) -> bool {
+        let first_break = self.steps.iter().position(|s| matches!(s, ScheduleStep::Break { .. }));
+        first_break == Some(self.index)
+    }
+
+    /// One-based number of the current level; a break keeps the number of the level before it.
+    pub fn level(&self) -> usize {
+        self.steps
+            .iter()
+            .take(self.index + 1)
+            .filter(|s| matches!(s, ScheduleStep::Level { .. }))
+            .count()
+    }
+
+    pub fn blinds(&self) -> Option<BlindLevel> {
+        self.steps.iter().take(self.index + 1).rev().find_map(Self::as_blinds)
+    }
+
+    pub fn status(&self, now: Instant) -> ClockStatus {
+        let last_step = self.index + 1 >= self.steps.len();
+        let (seconds_remaining, hands_remaining) = match (last_step, self.step_length()) {
+            (true, _) => (None, None),
+            (false, LevelDuration::Minutes(minutes)) => {
+                let elapsed = now.duration_since(self.step_started).as_secs();
+                (Some((minutes * 60).saturating_sub(elapsed)), None)
+            }
+            (false, LevelDuration::Hands(hands)) => (None, Some(hands.saturating_sub(self.hands_played))),
+        };
+
+        ClockStatus {
+            level: self.level(),
+            on_break: self.on_break(),
+            seconds_remaining,
+            hands_remaining,
+            next_level: self.steps.iter().skip(self.index + 1).find_map(Self::as_blinds),
+        }
+    }
+
+    fn step_length(&self) -> LevelDuration {
+        match self.steps.get(self.index) {
+            Some(ScheduleStep::Level { duration, .. }) => *duration,
+            Some(ScheduleStep::Break { minutes }) => LevelDuration::Minutes(*minutes),
+            None => LevelDuration::Hands(u32::MAX),
+        }
+    }
+
+    fn as_blinds(step: &ScheduleStep) -> Option<BlindLevel> {
+        match step {
+            ScheduleStep::Level { small_blind, big_blind, ante, .. } => Some(BlindLevel {
+                small_blind: *small_blind,
+                big_blind: *big_blind,
+                ante: *ante,
+            }),
+            ScheduleStep::Break { .. } => None,
+        }
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashSet;
+use std::time::Instant;
+use serde::{Serialize, Deserialize};
+
+use crate::domain::{
+    BlindLevel, DealMethod, DealProposal, DealShare, PrizePool, TournamentConfig, TournamentResult,
+};
+use crate::game::icm::{chip_chop, icm_equities};
+use crate::game::schedule::{ClockSnapshot, ScheduleClock};
+
+/// The clock holds an `Instant` and is persisted separately, see `clock_snapshot`.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Tournament {
+    pub config: TournamentConfig,
+    pub entrants: Vec<String>,
+    pub eliminated: Vec<String>,
+    reentries: usize,
+    rebuys: usize,
+    add_ons: HashSet<String>,
+    deal_request: Option<(String, DealMethod)>,
+    deal: Option<DealProposal>,
+    #[serde(skip)]
+    clock: Option<ScheduleClock>,
+    applied_level: Option<usize>,
+    finished: bool,
+}
+
+impl Tournament {
+    pub fn new(config: TournamentConfig) -> Self {
+        Self {
+            config,
+            entrants: vec![],
+            eliminated: vec![],
+            reentries: 0,
+            rebuys: 0,
+            add_ons: HashSet::new(),
+            deal_request: None,
+            deal: None,
+            clock: None,
+            applied_level: None,
+            finished: false,
+        }
+    }
+
+    pub fn register(&mut self, player_id: String) -> Result<(), String> {
+        if !self.registration_open() {
+            return Err("registration closed".into());
+        }
+        if self.entrants.contains(&player_id) {
+            return Err("already registered".into());
+        }
+        self.entrants.push(player_id);
+        Ok(())
+    }
+
+    /// Only before the start; returns whether the player was registered.
+    pub fn unregister(&mut self, player_id: &str) -> bool {
+        let before = self.entrants.len();
+        if self.clock.is_none() {
+            self.entrants.retain(|id| id != player_id);
+        }
+        self.entrants.len() < before
+    }
+
+    pub fn ready_to_start(&self) -> bool {
+        self.clock.is_none() && self.entrants.len() >= self.config.entrants
+    }
+
+    /// Open before the start and, with late registration, through the configured level.
+    pub fn registration_open(&self) -> bool {
+        match &self.clock {
+            None => true,
+            Some(clock) => {
+                !self.finished && self.config.late_registration_level.is_some_and(|last| clock.level() <= last)
+            }
+        }
+    }
+
+    /// Returns the chips for a new stack. The earlier elimination is forgotten; the final
+    /// standings only count where the player last went out.
+    pub fn reenter(&mut self, player_id: &str) -> Result<u64, String> {
+        if !self.config.reentry || !self.registration_open() {
+            return Err("re-entry closed".into());
+        }
+        let Some(index) = self.eliminated.iter().position(|id| id == player_id) else {
+            return Err("only eliminated players can re-enter".into());
+        };
+
+        self.eliminated.remove(index);
+        self.reentries += 1;
+        Ok(self.config.starting_stack)
+    }
+
+    pub fn rebuy(&mut self, player_id: &str, stack: u64) -> Result<u64, String> {
+        if !self.config.rebuys || !self.registration_open() {
+            return Err("rebuys closed".into());
+        }
+        if !self.is_alive(player_id) {
+            return Err("not in the tournament".into());
+        }
+        if stack > self.config.starting_stack {
+            return Err("rebuys are only allowed at or below the starting stack".into());
+        }
+
+        self.rebuys += 1;
+        Ok(self.config.starting_stack)
+    }
+
+    pub fn add_on(&mut self, player_id: &str) -> Result<u64, String> {
+        let Some(add_on) = &self.config.add_on else {
+            return Err("no add-on offered".into());
+        };
+        if !self.clock.as_ref().is_some_and(ScheduleClock::on_first_break) {
+            return Err("add-ons are only available at the first break".into());
+        }
+        if !self.is_alive(player_id) {
+            return Err("not in the tournament".into());
+        }
+        if !self.add_ons.insert(player_id.to_string()) {
+            return Err("add-on already taken".into());
+        }
+
+        Ok(add_on.chips)
+    }
+
+    fn is_alive(&self, player_id: &str) -> bool {
+        self.entrants.iter().any(|id| id == player_id) && !self.eliminated.iter().any(|id| id == player_id)
+    }
+
+    pub fn prize_pool_status(&self) -> PrizePool {
+        PrizePool {
+            entries: self.entrants.len() + self.reentries,
+            rebuys: self.rebuys,
+            add_ons: self.add_ons.len(),
+            prize_pool: self.prize_pool(),
+        }
+    }
+
+    pub fn start(&mut self, now: Instant) {
+        self.clock = Some(ScheduleClock::new(self.config.schedule.clone(), now));
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.clock.is_some() && !self.finished
+    }
+
+    pub fn clock_mut(&mut self) -> Option<&mut ScheduleClock> {
+        self.clock.as_mut()
+    }
+
+    pub fn clock_snapshot(&self, now: Instant) -> Option<ClockSnapshot> {
+        self.clock.as_ref().map(|clock| clock.snapshot(now))
+    }
+
+    pub fn restore_clock(&mut self, snapshot: ClockSnapshot, now: Instant) {
+        self.clock = Some(ScheduleClock::restore(snapshot, now));
+    }
+
+    pub fn on_break(&self) -> bool {
+        self.clock.as_ref().is_some_and(ScheduleClock::on_break)
+    }
+
+    pub fn current_blinds(&self) -> Option<BlindLevel> {
+        self.clock.as_ref()?.blinds()
+    }
+
+    /// Blinds for the next hand, when the level moved on since they were last handed out.
+    /// Levels only ever change between hands.
+    pub fn take_level_change(&mut self) -> Option<(usize, BlindLevel)> {
+        let clock = self.clock.as_ref()?;
+        let level = clock.level();
+        if self.applied_level == Some(level) { return None; }
+
+        let blinds = clock.blinds()?;
+        self.applied_level = Some(level);
+        Some((level, blinds))
+    }
+
+    /// Play stops after the current hand while a deal is being negotiated.
+    pub fn deal_pending(&self) -> bool {
+        self.deal_request.is_some() || self.deal.is_some()
+    }
+
+    pub fn request_deal(&mut self, proposer: &str, method: DealMethod) -> Result<(), String> {
+        if !self.is_running() {
+            return Err("tournament not running".into());
+        }
+        if !self.is_alive(proposer) {
+            return Err("not in the tournament".into());
+        }
+        if self.deal_pending() {
+            return Err("a deal is already being negotiated".into());
+        }
+
+        self.deal_request = Some((proposer.to_string(), method));
+        Ok(())
+    }
+
+    /// Prices the requested deal from the stacks of every player still in, once no hand is running.
+    pub fn open_deal(&mut self, stacks: &[(String, u64)]) -> Option<DealProposal> {
+        let (proposer, method) = self.deal_request.take()?;
+
+        let chips: Vec<u64> = stacks.iter().map(|(_, stack)| *stack).collect();
+        let prizes: Vec<u64> = self.payouts().into_iter().take(chips.len()).collect();
+        let icm = icm_equities(&chips, &prizes);
+        let chop = chip_chop(&chips, &prizes);
+
+        let shares = stacks
+            .iter()
+            .zip(icm.into_iter().zip(chop))
+            .map(|((player_id, stack), (icm, chip_chop))| DealShare {
+                player_id: player_id.clone(),
+                stack: *stack,
+                icm,
+                chip_chop,
+                amount: match method {
+                    DealMethod::Icm => icm,
+                    DealMethod::ChipChop => chip_chop,
+                },
+            })
+            .collect();
+
+        let proposal = DealProposal { proposer: proposer.clone(), method, shares, accepted: vec![proposer] };
+        self.deal = Some(proposal.clone());
+        Some(proposal)
+    }
+
+    /// Returns whether everyone in the deal has now accepted it.
+    pub fn accept_deal(&mut self, player_id: &str) -> Result<bool, String> {
+        let Some(deal) = &mut self.deal else {
+            return Err("no deal to accept".into());
+        };
+        if !deal.shares.iter().any(|s| s.player_id == player_id) {
+            return Err("not part of the deal".into());
+        }
+
+        if !deal.accepted.iter().any(|id| id == player_id) {
+            deal.accepted.push(player_id.to_string());
+        }
+        Ok(deal.accepted.len() == deal.shares.len())
+    }
+
+    pub fn reject_deal(&mut self, player_id: &str) -> Result<(), String> {
+        if !self.deal_pending() {
+            return Err("no deal to reject".into());
+        }
+        if !self.is_alive(player_id) {
+            return Err("not part of the deal".into());
+        }
+
+        self.deal_request = None;
+        self.deal = None;
+        Ok(())
+    }
+
+    /// Records players who busted in the same hand. `busted` holds each player's chips at the
+    /// start of the hand; the bigger starting stack finishes higher. Returns assigned positions,
+    /// which late registrations can still push down.
+    pub fn eliminate(&mut self, mut busted: Vec<(String, u64)>) -> Vec<(String, usize)> {
+        busted.sort_by_key(|(_, stack)| *stack);
+
+        busted
+            .into_iter()
+            .map(|(player_id, _)| {
+                let position = self.entrants.len() - self.eliminated.len();
+                self.eliminated.push(player_id.clone());
+                (player_id, position)
+            })
+            .collect()
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.entrants.len() - self.eliminated.len()
+    }
+
+    pub fn prize_pool(&self) -> u64 {
+        let buy_ins = (self.entrants.len() + self.reentries + self.rebuys) as u64;
+        let add_on_cost = self.config.add_on.as_ref().map_or(0, |a| a.cost);
+        self.config.buy_in * buy_ins + add_on_cost * self.add_ons.len() as u64
+    }
+
+    /// Splits the prize pool by the configured percentages; rounding leftovers go to first place.
+    pub fn payouts(&self) -> Vec<u64> {
+        let pool = self.prize_pool();
+        let mut payouts: Vec<u64> = self.config.payouts.iter().map(|pct| pool * pct / 100).collect();
+
+        let distributable = pool * self.config.payouts.iter().sum::<u64>() / 100;
+        let paid: u64 = payouts.iter().sum();
+        if let Some(first) = payouts.first_mut() {
+            *first += distributable - paid;
+        }
+        payouts
+    }
+
+    /// Standings with prizes. An agreed deal pays its shares to the players in it, placed by
+    /// chip count; everyone who busted earlier keeps the regular payout for their position.
+    pub fn finish(&mut self) -> Vec<TournamentResult> {
+        self.finished = true;
+        let mut prizes = self.payouts();
+
+        let agreed = self.deal.take().filter(|d| d.accepted.len() == d.shares.len());
+        let leaders: Vec<String> = match agreed {
+            Some(mut deal) => {
+                deal.shares.sort_by_key(|s| std::cmp::Reverse(s.stack));
+                for (i, share) in deal.shares.iter().enumerate() {
+                    match prizes.get_mut(i) {
+                        Some(prize) => *prize = share.amount,
+                        None => prizes.push(share.amount),
+                    }
+                }
+                deal.shares.into_iter().map(|s| s.player_id).collect()
+            }
+            None => self.entrants.iter().filter(|id| !self.eliminated.contains(id)).take(1).cloned().collect(),
+        };
+
+        leaders
+            .into_iter()
+            .chain(self.eliminated.iter().rev().cloned())
+            .enumerate()
+            .map(|(i, player_id)| TournamentResult {
+                player_id,
+                position: i + 1,
+                prize: prizes.get(i).copied().unwrap_or(0),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,5 @@
+pub mod store;
+pub mod tokens;
+
+pub use store::AccountStore;
+pub use tokens::SessionTokens;
@@ -0,0 +1,462 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use argon2::Argon2;
+use argon2::password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString};
+use chrono::{DateTime, Utc};
+use rusqlite::{Connection, OptionalExtension, Row, TransactionBehavior, params};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::domain::{
+    Account, CashOut, JournalEntry, JournalKind, LeaderboardEntry, LedgerAccount, LedgerRecord, Posting,
+};
+use crate::infrastructure::Flush;
+use crate::infrastructure::ledger::store as ledger;
+use crate::shared::{MAX_USERNAME_LEN, MIN_PASSWORD_LEN, MIN_USERNAME_LEN, STARTING_BANKROLL};
+
+const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS accounts (
+        id TEXT PRIMARY KEY,
+        username TEXT NOT NULL UNIQUE,
+        password_hash TEXT NOT NULL,
+        bankroll INTEGER NOT NULL CHECK (bankroll >= 0),
+        created_at TEXT NOT NULL
+    );
+    CREATE TABLE IF NOT EXISTS table_stacks (
+        account_id TEXT NOT NULL REFERENCES accounts(id),
+        table_id TEXT NOT NULL,
+        amount INTEGER NOT NULL,
+        PRIMARY KEY (account_id, table_id)
+    );
+";
+
+/// Player accounts, bankrolls and the chip ledger in SQLite. `table_stacks` holds the buy-ins each
+/// account still has open on a table, so every chip is either in a bankroll or on a table.
+#[derive(Clone)]
+pub struct AccountStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl AccountStore {
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let conn = Connection::open(path.into())?;
+        conn.execute_batch(SCHEMA)?;
+        conn.execute_batch(ledger::SCHEMA)?;
+        ledger::migrate(&conn)?;
+        Ok(Self { conn: Arc::new(Mutex::new(conn)) })
+    }
+
+    pub async fn register(&self, username: &str, password: &str) -> Result<Account, String> {
+        let username = username.trim().to_string();
+        if !(MIN_USERNAME_LEN..=MAX_USERNAME_LEN).contains(&username.chars().count())
+            || !username.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        {
+            return Err(format!(
+                "usernames are {MIN_USERNAME_LEN}-{MAX_USERNAME_LEN} letters, digits or underscores"
+            ));
+        }
+        if password.chars().count() < MIN_PASSWORD_LEN {
+            return Err(format!("passwords need at least {MIN_PASSWORD_LEN} characters"));
+        }
+
+        let password = password.to_string();
+        self.blocking(move |conn| {
+            let salt = SaltString::generate(&mut rand::rngs::OsRng);
+            let hash = Argon2::default()
+                .hash_password(password.as_bytes(), &salt)
+                .map_err(|e| e.to_string())?
+                .to_string();
+
+            let account = Account {
+                id: Uuid::new_v4().to_string(),
+                username,
+                bankroll: STARTING_BANKROLL,
+                created_at: Utc::now(),
+            };
+            let inserted = conn.execute(
+                "INSERT INTO accounts (id, username, password_hash, bankroll, created_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![account.id, account.username, hash, account.bankroll, account.created_at.to_rfc3339()],
+            );
+            match inserted {
+                Ok(_) => Ok(account),
+                Err(rusqlite::Error::SqliteFailure(e, _)) if e.code == rusqlite::ErrorCode::ConstraintViolation => {
+                    Err("username is taken".into())
+                }
+                Err(e) => Err(e.to_string()),
+            }
+        })
+        .await
+    }
+
+    pub async fn authenticate(&self, username: &str, password: &str) -> Result<Account, String> {
+        let username = username.trim().to_string();
+        let password = password.to_string();
+        self.blocking(move |conn| {
+            let found = conn
+                .query_row(
+                    "SELECT id, username, bankroll, created_at, password_hash FROM accounts WHERE username = ?1",
+                    params![username],
+                    |row| Ok((account_from_row(row)?, row.get::<_, String>(4)?)),
+                )
+                .optional()
+                .map_err(|e| e.to_string())?;
+
+            let Some((account, hash)) = found else {
+                return Err("invalid username or password".into());
+            };
+            let hash = PasswordHash::new(&hash).map_err(|e| e.to_string())?;
+            Argon2::default()
+                .verify_password(password.as_bytes(), &hash)
+                .map_err(|_| "invalid username or password".to_string())?;
+            Ok(account)
+        })
+        .await
+    }
+
+    pub async fn account(&self, account_id: &str) -> Result<Option<Account>, String> {
+        let account_id = account_id.to_string();
+        self.blocking(move |conn| {
+            conn.query_row(
+                "SELECT id, username, bankroll, created_at FROM accounts WHERE id = ?1",
+                params![account_id],
+                account_from_row,
+            )
+            .optional()
+            .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    /// The richest bankrolls first; chips sitting on tables do not count.
+    pub async fn leaderboard(&self, limit: usize) -> Result<Vec<LeaderboardEntry>, String> {
+        self.blocking(move |conn| {
+            let mut statement = conn
+                .prepare("SELECT username, bankroll FROM accounts ORDER BY bankroll DESC, username LIMIT ?1")
+                .map_err(|e| e.to_string())?;
+            let rows = statement
+                .query_map(params![limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(|e| e.to_string())?;
+
+            rows.enumerate()
+                .map(|(i, row)| row.map(|(username, bankroll)| LeaderboardEntry { rank: i + 1, username, bankroll }))
+                .collect::<Result<_, _>>()
+                .map_err(|e| e.to_string())
+        })
+        .await
+    }
+
+    /// Moves chips from the bankroll onto a table; returns the bankroll left.
+    pub async fn buy_in(&self, account_id: &str, table_id: &str, amount: u64) -> Result<u64, String> {
+        if amount == 0 {
+            return Err("buy-in must be positive".into());
+        }
+
+        let (account_id, table_id) = (account_id.to_string(), table_id.to_string());
+        self.blocking(move |conn| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+            let bankroll: u64 = tx
+                .query_row("SELECT bankroll FROM accounts WHERE id = ?1", params![account_id], |row| row.get(0))
+                .optional()
+                .map_err(|e| e.to_string())?
+                .ok_or("unknown account")?;
+            if bankroll < amount {
+                return Err(format!("bankroll of {bankroll} cannot cover a buy-in of {amount}"));
+            }
+
+            tx.execute("UPDATE accounts SET bankroll = bankroll - ?2 WHERE id = ?1", params![account_id, amount])
+                .map_err(|e| e.to_string())?;
+            tx.execute(
+                "INSERT INTO table_stacks (account_id, table_id, amount) VALUES (?1, ?2, ?3)
+                 ON CONFLICT (account_id, table_id) DO UPDATE SET amount = amount + excluded.amount",
+                params![account_id, table_id, amount],
+            )
+            .map_err(|e| e.to_string())?;
+            let bankroll_account = LedgerAccount::Bankroll { account_id: account_id.clone() };
+            let stack = LedgerAccount::Stack { table_id: table_id.clone(), player_id: account_id };
+            ledger::post(&tx, &JournalEntry::transfer(&table_id, None, JournalKind::BuyIn, bankroll_account, stack, amount))?;
+            tx.commit().map_err(|e| e.to_string())?;
+            Ok(bankroll - amount)
+        })
+        .await
+    }
+
+    /// Returns a player's table stack to their bankroll and closes the buy-in it came from; any
+    /// later buy-in on the same table stays open. Returns the new bankroll.
+    pub async fn cash_out(&self, cash_out: CashOut) -> Result<u64, String> {
+        self.blocking(move |conn| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+            let bankroll = settle(&tx, &cash_out.account_id, &cash_out.table_id, cash_out.bought, cash_out.amount)?;
+            tx.commit().map_err(|e| e.to_string())?;
+            Ok(bankroll)
+        })
+        .await
+    }
+
+    /// Hands back a buy-in the table turned away, leaving any seat the account already holds
+    /// there as it was.
+    pub async fn refund_buy_in(&self, account_id: &str, table_id: &str, amount: u64) -> Result<u64, String> {
+        let (account_id, table_id) = (account_id.to_string(), table_id.to_string());
+        self.blocking(move |conn| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+            let bought = tx
+                .execute(
+                    "UPDATE table_stacks SET amount = amount - ?3 WHERE account_id = ?1 AND table_id = ?2 AND amount >= ?3",
+                    params![account_id, table_id, amount],
+                )
+                .map_err(|e| e.to_string())?;
+            if bought == 0 {
+                return Err("no such buy-in on this table".into());
+            }
+            tx.execute(
+                "DELETE FROM table_stacks WHERE account_id = ?1 AND table_id = ?2 AND amount = 0",
+                params![account_id, table_id],
+            )
+            .map_err(|e| e.to_string())?;
+
+            let bankroll = credit(&tx, &account_id, &table_id, amount)?;
+            tx.commit().map_err(|e| e.to_string())?;
+            Ok(bankroll)
+        })
+        .await
+    }
+
+    /// Pays a tournament entry fee from the bankroll into the prize pool; returns the bankroll left.
+    pub async fn enter_tournament(&self, account_id: &str, table_id: &str, fee: u64) -> Result<u64, String> {
+        let (account_id, table_id) = (account_id.to_string(), table_id.to_string());
+        self.blocking(move |conn| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+            let bankroll: u64 = tx
+                .query_row("SELECT bankroll FROM accounts WHERE id = ?1", params![account_id], |row| row.get(0))
+                .optional()
+                .map_err(|e| e.to_string())?
+                .ok_or("unknown account")?;
+            if bankroll < fee {
+                return Err(format!("bankroll of {bankroll} cannot cover an entry fee of {fee}"));
+            }
+
+            tx.execute("UPDATE accounts SET bankroll = bankroll - ?2 WHERE id = ?1", params![account_id, fee])
+                .map_err(|e| e.to_string())?;
+            let bankroll_account = LedgerAccount::Bankroll { account_id };
+            let entry = JournalEntry::transfer(&table_id, None, JournalKind::EntryFee, bankroll_account, LedgerAccount::PrizePool, fee);
+            if fee > 0 {
+                ledger::post(&tx, &entry)?;
+            }
+            tx.commit().map_err(|e| e.to_string())?;
+            Ok(bankroll - fee)
+        })
+        .await
+    }
+
+    /// Hands an entry fee back from the prize pool; returns the new bankroll.
+    pub async fn refund_entry(&self, account_id: &str, table_id: &str, fee: u64) -> Result<u64, String> {
+        self.pay_from_prize_pool(account_id, table_id, JournalKind::EntryFee, fee).await
+    }
+
+    /// Pays a tournament prize from the prize pool; returns the new bankroll.
+    pub async fn pay_prize(&self, account_id: &str, table_id: &str, prize: u64) -> Result<u64, String> {
+        self.pay_from_prize_pool(account_id, table_id, JournalKind::Prize, prize).await
+    }
+
+    async fn pay_from_prize_pool(&self, account_id: &str, table_id: &str, kind: JournalKind, amount: u64) -> Result<u64, String> {
+        let (account_id, table_id) = (account_id.to_string(), table_id.to_string());
+        self.blocking(move |conn| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+            let bankroll: u64 = tx
+                .query_row(
+                    "UPDATE accounts SET bankroll = bankroll + ?2 WHERE id = ?1 RETURNING bankroll",
+                    params![account_id, amount],
+                    |row| row.get(0),
+                )
+                .map_err(|e| e.to_string())?;
+            let bankroll_account = LedgerAccount::Bankroll { account_id };
+            let entry = JournalEntry::transfer(&table_id, None, kind, LedgerAccount::PrizePool, bankroll_account, amount);
+            if amount > 0 {
+                ledger::post(&tx, &entry)?;
+            }
+            tx.commit().map_err(|e| e.to_string())?;
+            Ok(bankroll)
+        })
+        .await
+    }
+
+    /// Refunds the buy-ins still open on a table whose state was lost and writes off whatever
+    /// else the ledger still has on it.
+    pub async fn refund_table(&self, table_id: &str) -> Result<usize, String> {
+        let table_id = table_id.to_string();
+        self.blocking(move |conn| {
+            let tx = conn.transaction_with_behavior(TransactionBehavior::Immediate).map_err(|e| e.to_string())?;
+            let open: Vec<(String, u64)> = {
+                let mut statement = tx
+                    .prepare("SELECT account_id, amount FROM table_stacks WHERE table_id = ?1")
+                    .map_err(|e| e.to_string())?;
+                let rows = statement
+                    .query_map(params![table_id], |row| Ok((row.get(0)?, row.get(1)?)))
+                    .map_err(|e| e.to_string())?;
+                rows.collect::<Result<_, _>>().map_err(|e| e.to_string())?
+            };
+            for (account_id, amount) in &open {
+                settle(&tx, account_id, &table_id, *amount, *amount)?;
+            }
+
+            let left = ledger::table_balances(&tx, &table_id)?;
+            if !left.is_empty() {
+                let mut postings: Vec<Posting> = vec![];
+                let mut written_off = 0;
+                for (account, balance) in left {
+                    postings.push(Posting { account, amount: -balance });
+                    written_off += balance;
+                }
+                postings.push(Posting { account: LedgerAccount::WriteOff.to_string(), amount: written_off });
+                let entry = JournalEntry {
+                    table_id: table_id.clone(),
+                    hand_id: None,
+                    kind: JournalKind::WriteOff,
+                    at: Utc::now(),
+                    postings,
+                    memo: None,
+                };
+                ledger::post(&tx, &entry)?;
+            }
+            tx.commit().map_err(|e| e.to_string())?;
+            Ok(open.len())
+        })
+        .await
+    }
+
+    /// Spawns the ledger writer. Tables send their records in the order their chips moved, so a
+    /// checkpoint is compared against exactly the journals written before it.
+    pub fn start_ledger_writer(&self, mut flush: Flush) -> (mpsc::UnboundedSender<LedgerRecord>, JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::unbounded_channel::<LedgerRecord>();
+        let store = self.clone();
+
+        let writer = tokio::spawn(async move {
+            while let Some(record) = flush.next(&mut rx).await {
+                match record {
+                    LedgerRecord::Journal(entry) => {
+                        let table_id = entry.table_id.clone();
+                        if let Err(e) = store.blocking(move |conn| ledger::post(conn, &entry)).await {
+                            tracing::error!(%table_id, "could not write journal: {e}");
+                        }
+                    }
+                    LedgerRecord::CashOut(cash_out) => {
+                        let (account_id, amount) = (cash_out.account_id.clone(), cash_out.amount);
+                        if let Err(e) = store.cash_out(cash_out).await {
+                            tracing::error!(%account_id, amount, "could not cash out: {e}");
+                        }
+                    }
+                    LedgerRecord::Refund(refund) => {
+                        if let Err(e) = store.refund_entry(&refund.account_id, &refund.table_id, refund.amount).await {
+                            tracing::error!(account_id = %refund.account_id, amount = refund.amount, "could not refund entry: {e}");
+                        }
+                    }
+                    LedgerRecord::Prize(prize) => {
+                        if let Err(e) = store.pay_prize(&prize.account_id, &prize.table_id, prize.amount).await {
+                            tracing::error!(account_id = %prize.account_id, amount = prize.amount, "could not pay prize: {e}");
+                        }
+                    }
+                    LedgerRecord::Checkpoint { table_id, stacks, pot, reply } => {
+                        let checked = store.blocking(move |conn| ledger::reconcile_table(conn, &table_id, stacks, pot)).await;
+                        match checked {
+                            Ok(reconciliation) => { let _ = reply.send(reconciliation); }
+                            Err(e) => tracing::error!("could not reconcile: {e}"),
+                        }
+                    }
+                }
+            }
+        });
+
+        (tx, writer)
+    }
+
+    /// Journal ids whose postings do not sum to zero.
+    pub async fn unbalanced_journals(&self) -> Result<Vec<i64>, String> {
+        self.blocking(|conn| ledger::unbalanced_journals(conn)).await
+    }
+
+    async fn blocking<T: Send + 'static>(
+        &self,
+        work: impl FnOnce(&mut Connection) -> Result<T, String> + Send + 'static,
+    ) -> Result<T, String> {
+        let conn = self.conn.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut conn = conn.lock().map_err(|_| "account database lock poisoned".to_string())?;
+            work(&mut conn)
+        })
+        .await
+        .map_err(|e| e.to_string())?
+    }
+}
+
+/// Closes `bought` of the account's open buy-ins on the table and pays `amount` into its bankroll.
+fn settle(tx: &Connection, account_id: &str, table_id: &str, bought: u64, amount: u64) -> Result<u64, String> {
+    let seated = tx
+        .execute(
+            "UPDATE table_stacks SET amount = amount - ?3 WHERE account_id = ?1 AND table_id = ?2 AND amount >= ?3",
+            params![account_id, table_id, bought],
+        )
+        .map_err(|e| e.to_string())?;
+    if seated == 0 {
+        return Err("account has no chips on this table".into());
+    }
+    tx.execute(
+        "DELETE FROM table_stacks WHERE account_id = ?1 AND table_id = ?2 AND amount = 0",
+        params![account_id, table_id],
+    )
+    .map_err(|e| e.to_string())?;
+    credit(tx, account_id, table_id, amount)
+}
+
+/// Moves chips from the account's stack on a table back to its bankroll.
+fn credit(tx: &Connection, account_id: &str, table_id: &str, amount: u64) -> Result<u64, String> {
+    let bankroll: u64 = tx
+        .query_row(
+            "UPDATE accounts SET bankroll = bankroll + ?2 WHERE id = ?1 RETURNING bankroll",
+            params![account_id, amount],
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    let stack = LedgerAccount::Stack { table_id: table_id.to_string(), player_id: account_id.to_string() };
+    let bankroll_account = LedgerAccount::Bankroll { account_id: account_id.to_string() };
+    ledger::post(tx, &JournalEntry::transfer(table_id, None, JournalKind::CashOut, stack, bankroll_account, amount))?;
+    Ok(bankroll)
+}
+
+fn account_from_row(row: &Row) -> rusqlite::Result<Account> {
+    let created_at: String = row.get(3)?;
+    Ok(Account {
+        id: row.get(0)?,
+        username: row.get(1)?,
+        bankroll: row.get(2)?,
+        created_at: DateTime::parse_from_rfc3339(&created_at)
+            .map(|t| t.with_timezone(&Utc))
+            .map_err(|e| rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e)))?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn account(store: &AccountStore, username: &str) -> String {
+        store.register(username, "password").await.unwrap().id
+    }
+
+    async fn bankroll(store: &AccountStore, account_id: &str) -> u64 {
+        store.account(account_id).await.unwrap().unwrap().bankroll
+    }
+
+    #[tokio::test]
+    async fn cash_out_leaves_a_later_buy_in_open() {
+        let store = AccountStore::open(":memory:").unwrap();
+        let alice = account(&store, "alice").await;
+        store.buy_in(&alice, "t", 100).await.unwrap();
+        store.buy_in(&alice, "t", 200).await.unwrap();
+
+        let cash_out = |amount, bought| CashOut { account_id: alice.clone(), table_id: "t".into(), amount, bought };
+        store.cash_out(cash_out(150, 100)).await.unwrap();
+        store.cash_out(cash_out(250, 200)).await.unwrap();
+        assert!(store.cash_out(cash_out(1, 1)).await.is_err());
+        assert_eq!(bankroll(&store, &alice).await, STARTING_BANKROLL + 100);
+    }
+}
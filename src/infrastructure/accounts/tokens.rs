@@ -0,0 +1,59 @@
+use std::sync::Arc;
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{Algorithm, DecodingKey, EncodingKey, Header, Validation, decode, encode};
+use rand::RngCore;
+use serde::{Serialize, Deserialize};
+
+use crate::shared::SESSION_TOKEN_TTL_SECS;
+
+#[derive(Serialize, Deserialize)]
+struct Claims {
+    sub: String,
+    iat: i64,
+    exp: i64,
+}
+
+/// Signs and checks the HS256 session tokens handed out at login. The subject is the account id.
+#[derive(Clone)]
+pub struct SessionTokens {
+    encoding: Arc<EncodingKey>,
+    decoding: Arc<DecodingKey>,
+}
+
+impl SessionTokens {
+    pub fn new(secret: &[u8]) -> Self {
+        Self {
+            encoding: Arc::new(EncodingKey::from_secret(secret)),
+            decoding: Arc::new(DecodingKey::from_secret(secret)),
+        }
+    }
+
+    /// A throwaway secret; tokens stop working when the server restarts.
+    pub fn ephemeral() -> Self {
+        let mut secret = [0u8; 32];
+        rand::rngs::OsRng.fill_bytes(&mut secret);
+        Self::new(&secret)
+    }
+
+    pub fn issue(&self, account_id: &str) -> Result<(String, DateTime<Utc>), String> {
+        let now = Utc::now();
+        let expires_at = now + Duration::seconds(SESSION_TOKEN_TTL_SECS);
+        let claims = Claims { sub: account_id.to_string(), iat: now.timestamp(), exp: expires_at.timestamp() };
+
+        let token = encode(&Header::new(Algorithm::HS256), &claims, &self.encoding).map_err(|e| e.to_string())?;
+        Ok((token, expires_at))
+    }
+
+    /// The account a token was issued to, if the signature holds and it has not expired.
+    pub fn verify(&self, token: &str) -> Result<String, String> {
+        let mut validation = Validation::new(Algorithm::HS256);
+        validation.leeway = 0;
+
+        decode::<Claims>(token, &self.decoding, &validation)
+            .map(|data| data.claims.sub)
+            .map_err(|e| match e.kind() {
+                jsonwebtoken::errors::ErrorKind::ExpiredSignature => "session expired".to_string(),
+                _ => "invalid session token".to_string(),
+            })
+    }
+}
@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::domain::{GameLogEntry, LoggedCommand};
+use crate::game::TableSnapshot;
+use crate::infrastructure::Flush;
+
+/// Per-table command logs, one JSON-lines file per table in a directory, next to each table's
+/// latest snapshot. Once a snapshot is on disk the log is cut back to the entries after it, so
+/// a table's files stay as small as its last snapshot interval.
+#[derive(Clone)]
+pub struct GameLogStore {
+    dir: Arc<PathBuf>,
+}
+
+impl GameLogStore {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: Arc::new(dir.into()) }
+    }
+
+    /// Spawns the writer; every entry is on disk before the next one is taken. The writer also
+    /// takes `(table_id, sequence)` cuts from the snapshot writer, see `cut`.
+    pub fn start_writer(
+        &self,
+        mut flush: Flush,
+    ) -> (mpsc::UnboundedSender<GameLogEntry>, mpsc::UnboundedSender<(String, u64)>, JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::unbounded_channel::<GameLogEntry>();
+        let (cuts_tx, mut cuts) = mpsc::unbounded_channel::<(String, u64)>();
+        let dir = self.dir.clone();
+
+        let writer = tokio::spawn(async move {
+            let mut files = HashMap::new();
+            loop {
+                tokio::select! {
+                    entry = flush.next(&mut rx) => {
+                        let Some(entry) = entry else { break };
+                        if let Err(e) = append(&dir, &mut files, &entry).await {
+                            tracing::error!(table_id = %entry.table_id, sequence = entry.sequence, "could not write game log: {e}");
+                        }
+                    }
+                    Some((table_id, sequence)) = cuts.recv() => {
+                        if let Err(e) = cut(&dir, &mut files, &table_id, sequence).await {
+                            tracing::error!(%table_id, sequence, "could not cut game log: {e}");
+                        }
+                    }
+                }
+            }
+        });
+
+        (tx, cuts_tx, writer)
+    }
+
+    /// Spawns the snapshot writer. A snapshot replaces the previous one only once fully on disk,
+    /// and only then is the log cut back to it.
+    pub fn start_snapshot_writer(
+        &self,
+        mut flush: Flush,
+        cuts: mpsc::UnboundedSender<(String, u64)>,
+    ) -> (mpsc::UnboundedSender<TableSnapshot>, JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::unbounded_channel::<TableSnapshot>();
+        let store = self.clone();
+
+        let writer = tokio::spawn(async move {
+            while let Some(snapshot) = flush.next(&mut rx).await {
+                match store.write_snapshot(&snapshot).await {
+                    Ok(()) => { let _ = cuts.send((snapshot.table_id, snapshot.sequence)); }
+                    Err(e) => tracing::error!(table_id = %snapshot.table_id, sequence = snapshot.sequence, "could not write snapshot: {e}"),
+                }
+            }
+        });
+
+        (tx, writer)
+    }
+
+    fn path_for(&self, table_id: &str) -> PathBuf {
+        self.dir.join(format!("{table_id}.jsonl"))
+    }
+
+    fn snapshot_path_for(&self, table_id: &str) -> PathBuf {
+        self.dir.join(format!("{table_id}.snapshot.json"))
+    }
+
+    pub async fn read_snapshot(&self, table_id: &str) -> anyhow::Result<Option<TableSnapshot>> {
+        match tokio::fs::read_to_string(self.snapshot_path_for(table_id)).await {
+            Ok(raw) => Ok(Some(serde_json::from_str(&raw)?)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn write_snapshot(&self, snapshot: &TableSnapshot) -> anyhow::Result<()> {
+        tokio::fs::create_dir_all(self.dir.as_ref()).await?;
+        let path = self.snapshot_path_for(&snapshot.table_id);
+        let partial = path.with_extension("json.partial");
+
+        let mut file = File::create(&partial).await?;
+        file.write_all(&serde_json::to_vec(snapshot)?).await?;
+        file.sync_data().await?;
+        tokio::fs::rename(&partial, &path).await?;
+        Ok(())
+    }
+
+    /// Every table that has written a log, whether or not it is still open.
+    pub async fn table_ids(&self) -> anyhow::Result<Vec<String>> {
+        let mut dir = match tokio::fs::read_dir(self.dir.as_ref()).await {
+            Ok(dir) => dir,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut table_ids = vec![];
+        while let Some(entry) = dir.next_entry().await? {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == "jsonl") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    table_ids.push(stem.to_string());
+                }
+            }
+        }
+        table_ids.sort();
+        Ok(table_ids)
+    }
+
+    /// The entries of the table's most recent session: its opening, then everything from its
+    /// latest snapshot on.
+    pub async fn read(&self, table_id: &str) -> anyhow::Result<Vec<GameLogEntry>> {
+        read_file(&self.path_for(table_id)).await
+    }
+}
+
+async fn read_file(path: &Path) -> anyhow::Result<Vec<GameLogEntry>> {
+    let raw = match tokio::fs::read_to_string(path).await {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = vec![];
+    for line in raw.lines().filter(|l| !l.trim().is_empty()) {
+        let entry: GameLogEntry = serde_json::from_str(line)?;
+        if matches!(entry.command, LoggedCommand::Opened { .. }) {
+            entries.clear();
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
+
+/// Drops the entries a snapshot taken at `sequence` already holds. The snapshot's own entry stays
+/// so recovery can match the two, and so does the opening, which carries the table's config. The
+/// shorter log replaces the old one only once it is on disk; the next append reopens it.
+async fn cut(dir: &Path, files: &mut HashMap<String, File>, table_id: &str, sequence: u64) -> anyhow::Result<()> {
+    files.remove(table_id);
+    let path = dir.join(format!("{table_id}.jsonl"));
+    let raw = match tokio::fs::read_to_string(&path).await {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut kept = String::new();
+    for line in raw.lines().filter(|l| !l.trim().is_empty()) {
+        let entry: GameLogEntry = serde_json::from_str(line)?;
+        if entry.sequence >= sequence || matches!(entry.command, LoggedCommand::Opened { .. }) {
+            kept.push_str(line);
+            kept.push('\n');
+        }
+    }
+
+    let partial = path.with_extension("jsonl.partial");
+    let mut file = File::create(&partial).await?;
+    file.write_all(kept.as_bytes()).await?;
+    file.sync_data().await?;
+    tokio::fs::rename(&partial, &path).await?;
+    Ok(())
+}
+
+async fn append(dir: &Path, files: &mut HashMap<String, File>, entry: &GameLogEntry) -> anyhow::Result<()> {
+    let mut line = serde_json::to_string(entry)?;
+    line.push('\n');
+
+    if !files.contains_key(&entry.table_id) {
+        tokio::fs::create_dir_all(dir).await?;
+        let path = dir.join(format!("{}.jsonl", entry.table_id));
+        let file = OpenOptions::new().create(true).append(true).open(path).await?;
+        files.insert(entry.table_id.clone(), file);
+    }
+
+    let file = files.get_mut(&entry.table_id).expect("opened above");
+    file.write_all(line.as_bytes()).await?;
+    file.sync_data().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{GameVariant, RngSeed, TableConfig};
+
+    fn entry(sequence: u64, command: LoggedCommand) -> GameLogEntry {
+        GameLogEntry { table_id: "t".into(), sequence, elapsed_ns: 0, command }
+    }
+
+    #[tokio::test]
+    async fn cut_keeps_the_opening_and_the_entries_from_the_snapshot_on() {
+        let dir = std::env::temp_dir().join(format!("game-log-cut-{}", uuid::Uuid::new_v4()));
+        let store = GameLogStore::new(&dir);
+        let mut files = HashMap::new();
+
+        let config = Box::new(TableConfig::single(GameVariant::Holdem));
+        append(&dir, &mut files, &entry(0, LoggedCommand::Opened { config, seed: RngSeed::Number(7), managed: None })).await.unwrap();
+        for sequence in 1..5 {
+            append(&dir, &mut files, &entry(sequence, LoggedCommand::Reseed { seed: RngSeed::Number(sequence) })).await.unwrap();
+        }
+        cut(&dir, &mut files, "t", 3).await.unwrap();
+        append(&dir, &mut files, &entry(5, LoggedCommand::ClockTick)).await.unwrap();
+
+        let sequences: Vec<u64> = store.read("t").await.unwrap().iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![0, 3, 4, 5]);
+        tokio::fs::remove_dir_all(&dir).await.unwrap();
+    }
+}
@@ -0,0 +1,7 @@
+pub mod store;
+pub mod pokerstars;
+pub mod ohh;
+
+pub use store::HandHistoryStore;
+pub use pokerstars::to_pokerstars;
+pub use ohh::export_ohh;
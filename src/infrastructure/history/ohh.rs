@@ -0,0 +1,308 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::domain::{
+    BettingStructure, Card, GameVariant, HandHistory, RecordedAction, RoundPhase,
+};
+use crate::infrastructure::history::HandHistoryStore;
+
+const SPEC_VERSION: &str = "1.4.6";
+const SITE_NAME: &str = "ws_cards_server";
+const TABLE_SIZE: usize = 9;
+
+/// A hand in the Open Hand History standard, ready to serialize.
+#[derive(Serialize)]
+pub struct OhhDocument {
+    pub ohh: OpenHandHistory,
+}
+
+#[derive(Serialize)]
+pub struct OpenHandHistory {
+    pub spec_version: String,
+    pub site_name: String,
+    pub network_name: String,
+    pub internal_version: String,
+    pub tournament: bool,
+    pub game_number: String,
+    pub start_date_utc: DateTime<Utc>,
+    pub table_name: String,
+    pub game_type: String,
+    pub bet_limit: OhhBetLimit,
+    pub table_size: usize,
+    pub currency: String,
+    pub dealer_seat: usize,
+    pub small_blind_amount: u64,
+    pub big_blind_amount: u64,
+    pub ante_amount: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hero_player_id: Option<usize>,
+    pub flags: Vec<String>,
+    pub players: Vec<OhhPlayer>,
+    pub rounds: Vec<OhhRound>,
+    pub pots: Vec<OhhPot>,
+}
+
+#[derive(Serialize)]
+pub struct OhhBetLimit {
+    pub bet_type: String,
+    pub bet_cap: u64,
+}
+
+#[derive(Serialize)]
+pub struct OhhPlayer {
+    pub id: usize,
+    pub seat: usize,
+    pub name: String,
+    pub display: String,
+    pub starting_stack: u64,
+}
+
+#[derive(Serialize)]
+pub struct OhhRound {
+    pub id: usize,
+    pub street: String,
+    pub cards: Vec<String>,
+    pub actions: Vec<OhhAction>,
+}
+
+#[derive(Serialize)]
+pub struct OhhAction {
+    pub action_number: usize,
+    pub player_id: usize,
+    pub action: String,
+    pub amount: u64,
+    pub is_allin: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cards: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct OhhPot {
+    pub number: usize,
+    pub amount: u64,
+    pub rake: u64,
+    pub jackpot: u64,
+    pub player_wins: Vec<OhhWin>,
+}
+
+#[derive(Serialize)]
+pub struct OhhWin {
+    pub player_id: usize,
+    pub win_amount: u64,
+    pub contributed_rake: u64,
+}
+
+/// Maps a recorded hand onto OHH. Players are numbered by seat order; only `hero`'s hole
+/// cards are dealt face up, everyone else's appear only if shown down.
+pub fn to_ohh(h: &HandHistory, hero: Option<&str>) -> OhhDocument {
+    let players: Vec<OhhPlayer> = h.seats
+        .iter()
+        .enumerate()
+        .map(|(i, seat)| OhhPlayer {
+            id: i + 1,
+            seat: seat.seat + 1,
+            name: seat.player_id.clone(),
+            display: seat.player_id.clone(),
+            starting_stack: seat.stack,
+        })
+        .collect();
+    let id_of = |seat: usize| players.iter().find(|p| p.seat == seat + 1).map_or(0, |p| p.id);
+    let hero_player_id = hero.and_then(|hero| players.iter().find(|p| p.name == hero).map(|p| p.id));
+
+    // Community-card streets come from the board, so an all-in runout still shows every street.
+    let board = h.boards.first().cloned().unwrap_or_default();
+    let mut rounds = vec![OhhRound { id: 0, street: "Preflop".into(), cards: vec![], actions: vec![] }];
+    for (street, dealt) in [("Flop", 0..3), ("Turn", 3..4), ("River", 4..5)] {
+        if let Some(cards) = board.get(dealt) {
+            let cards = cards.iter().map(Card::to_string).collect();
+            rounds.push(OhhRound { id: rounds.len(), street: street.into(), cards, actions: vec![] });
+        }
+    }
+
+    let mut action_number = 0;
+    if let Some(seat) = h.seats.iter().find(|s| Some(s.player_id.as_str()) == hero) {
+        action_number += 1;
+        rounds[0].actions.push(OhhAction {
+            action_number,
+            player_id: id_of(seat.seat),
+            action: "Dealt Cards".into(),
+            amount: 0,
+            is_allin: false,
+            cards: seat.cards.iter().map(Card::to_string).collect(),
+        });
+    }
+
+    let mut stacks: BTreeMap<usize, u64> = h.seats.iter().map(|s| (s.seat, s.stack)).collect();
+    let mut street = "Preflop";
+    let mut street_bet = 0;
+
+    for action in &h.actions {
+        let Some(name) = street_name(&action.phase) else { continue; };
+        if name != street {
+            street = name;
+            street_bet = 0;
+        }
+        let round = match rounds.iter().position(|r| r.street == street) {
+            Some(round) => round,
+            None => {
+                rounds.push(OhhRound { id: rounds.len(), street: street.into(), cards: vec![], actions: vec![] });
+                rounds.len() - 1
+            }
+        };
+
+        let stack = stacks.entry(action.seat).or_default();
+        *stack = stack.saturating_sub(action.amount);
+        let name = action_name(&action.action, action.total_bet, street_bet);
+        street_bet = street_bet.max(action.total_bet);
+        let Some(name) = name else { continue; };
+
+        action_number += 1;
+        rounds[round].actions.push(OhhAction {
+            action_number,
+            player_id: id_of(action.seat),
+            action: name.into(),
+            amount: action.amount,
+            is_allin: *stack == 0 && action.amount > 0,
+            cards: vec![],
+        });
+    }
+
+    if !h.showdown.is_empty() {
+        let mut showdown = OhhRound { id: rounds.len(), street: "Showdown".into(), cards: vec![], actions: vec![] };
+        for shown in h.showdown.iter().filter(|r| r.board == 0) {
+            action_number += 1;
+            showdown.actions.push(OhhAction {
+                action_number,
+                player_id: id_of(shown.seat),
+                action: "Shows Cards".into(),
+                amount: 0,
+                is_allin: false,
+                cards: shown.hand.iter().map(Card::to_string).collect(),
+            });
+        }
+        rounds.push(showdown);
+    }
+
+    let mut pots: BTreeMap<usize, OhhPot> = BTreeMap::new();
+    for award in &h.winners {
+        let pot = pots.entry(award.pot_index).or_insert_with(|| OhhPot {
+            number: award.pot_index,
+            amount: 0,
+            rake: 0,
+            jackpot: 0,
+            player_wins: vec![],
+        });
+        pot.amount += award.amount;
+
+        let player_id = id_of(award.seat);
+        match pot.player_wins.iter_mut().find(|w| w.player_id == player_id) {
+            Some(win) => win.win_amount += award.amount,
+            None => pot.player_wins.push(OhhWin { player_id, win_amount: award.amount, contributed_rake: 0 }),
+        }
+    }
+    let mut pots: Vec<OhhPot> = pots.into_values().collect();
+    if let Some(main) = pots.first_mut() {
+        main.rake = h.rake;
+        main.amount += h.rake;
+    }
+
+    let mut flags = vec![];
+    if h.boards.len() > 1 {
+        flags.push("Run_It_Twice".to_string());
+    }
+
+    OhhDocument {
+        ohh: OpenHandHistory {
+            spec_version: SPEC_VERSION.into(),
+            site_name: SITE_NAME.into(),
+            network_name: SITE_NAME.into(),
+            internal_version: env!("CARGO_PKG_VERSION").into(),
+            tournament: h.tournament,
+            game_number: h.hand_id.clone(),
+            start_date_utc: h.started_at,
+            table_name: h.table_id.clone(),
+            game_type: game_type(h.variant).into(),
+            bet_limit: OhhBetLimit { bet_type: bet_type(h.betting_structure).into(), bet_cap: 0 },
+            table_size: TABLE_SIZE,
+            currency: "CHIPS".into(),
+            dealer_seat: h.button_seat + 1,
+            small_blind_amount: h.small_blind,
+            big_blind_amount: h.big_blind,
+            ante_amount: h.ante,
+            hero_player_id,
+            flags,
+            players,
+            rounds,
+            pots,
+        },
+    }
+}
+
+/// Writes every stored hand as OHH, one JSON document per line; returns how many were written.
+pub async fn export_ohh(store: &HandHistoryStore, out: impl AsRef<Path>) -> anyhow::Result<usize> {
+    let histories = store.all().await?;
+    let mut file = tokio::fs::File::create(out).await?;
+
+    for history in &histories {
+        let mut line = serde_json::to_string(&to_ohh(history, None))?;
+        line.push('\n');
+        file.write_all(line.as_bytes()).await?;
+    }
+    file.sync_all().await?;
+    Ok(histories.len())
+}
+
+fn game_type(variant: GameVariant) -> &'static str {
+    match variant {
+        GameVariant::Holdem => "Holdem",
+        GameVariant::FiveCardDraw | GameVariant::TripleDraw27 => "Draw",
+    }
+}
+
+fn bet_type(structure: BettingStructure) -> &'static str {
+    match structure {
+        BettingStructure::NoLimit => "NL",
+        BettingStructure::PotLimit => "PL",
+        BettingStructure::FixedLimit => "FL",
+    }
+}
+
+/// Runout votes have no OHH round of their own; the showdown round is added after the actions.
+fn street_name(phase: &RoundPhase) -> Option<&'static str> {
+    let name = match phase {
+        RoundPhase::Flop => "Flop",
+        RoundPhase::Turn => "Turn",
+        RoundPhase::River => "River",
+        RoundPhase::Draw { round: 1 } | RoundPhase::PostDraw { round: 1 } => "First Draw",
+        RoundPhase::Draw { round: 2 } | RoundPhase::PostDraw { round: 2 } => "Second Draw",
+        RoundPhase::Draw { .. } | RoundPhase::PostDraw { .. } => "Third Draw",
+        RoundPhase::RunoutVote => return None,
+        _ => "Preflop",
+    };
+    Some(name)
+}
+
+/// All-ins are reported as whatever they amounted to; runout votes have no OHH equivalent.
+fn action_name(action: &RecordedAction, total_bet: u64, street_bet: u64) -> Option<&'static str> {
+    let name = match action {
+        RecordedAction::Ante => "Post Ante",
+        RecordedAction::SmallBlind => "Post SB",
+        RecordedAction::BigBlind => "Post BB",
+        RecordedAction::Fold => "Fold",
+        RecordedAction::Check => "Check",
+        RecordedAction::Call => "Call",
+        RecordedAction::Bet => "Bet",
+        RecordedAction::Raise => "Raise",
+        RecordedAction::AllIn if total_bet <= street_bet => "Call",
+        RecordedAction::AllIn if street_bet == 0 => "Bet",
+        RecordedAction::AllIn => "Raise",
+        RecordedAction::Draw { discarded: 0 } => "Stand Pat",
+        RecordedAction::Draw { .. } => "Discard",
+        RecordedAction::RunBoards { .. } => return None,
+    };
+    Some(name)
+}
@@ -0,0 +1,261 @@
+use std::collections::HashMap;
+use std::fmt::Write;
+
+use uuid::Uuid;
+
+use crate::domain::{
+    ActionRecord, BettingStructure, Card, GameVariant, HandHistory, RecordedAction, RoundPhase,
+};
+
+const ORDINALS: [&str; 3] = ["FIRST", "SECOND", "THIRD"];
+
+/// Renders a hand the way PokerStars writes its text histories. Only `hero`'s hole cards are
+/// shown, plus whatever was tabled at showdown.
+pub fn to_pokerstars(history: &HandHistory, hero: Option<&str>) -> String {
+    let mut out = String::new();
+    let _ = write_hand(&mut out, history, hero);
+    out
+}
+
+fn write_hand(out: &mut String, h: &HandHistory, hero: Option<&str>) -> std::fmt::Result {
+    writeln!(
+        out,
+        "PokerStars Hand #{}:  {} {} ({}/{}) - {}",
+        hand_number(h),
+        game_name(h.variant),
+        limit_name(h.betting_structure),
+        h.small_blind,
+        h.big_blind,
+        h.started_at.format("%Y/%m/%d %H:%M:%S UTC"),
+    )?;
+    writeln!(out, "Table '{}' 9-max Seat #{} is the button", h.table_id, h.button_seat + 1)?;
+    for seat in &h.seats {
+        writeln!(out, "Seat {}: {} ({} in chips)", seat.seat + 1, seat.player_id, seat.stack)?;
+    }
+
+    let mut stacks: HashMap<&str, u64> = h.seats.iter().map(|s| (s.player_id.as_str(), s.stack)).collect();
+    let board = h.boards.first().cloned().unwrap_or_default();
+    let mut street_bet = 0;
+    let mut phase: Option<&RoundPhase> = None;
+    let mut shown_cards = 0;
+
+    for action in &h.actions {
+        let blind = matches!(action.action, RecordedAction::Ante | RecordedAction::SmallBlind | RecordedAction::BigBlind);
+        if !blind && phase != Some(&action.phase) {
+            if phase.is_none() {
+                write_dealt(out, h, hero)?;
+            }
+            phase = Some(&action.phase);
+            street_bet = if matches!(action.phase, RoundPhase::Preflop | RoundPhase::Predraw) { h.big_blind } else { 0 };
+            shown_cards = write_street(out, &action.phase, &board, shown_cards, None)?;
+        }
+
+        let stack = stacks.entry(action.player_id.as_str()).or_default();
+        *stack = stack.saturating_sub(action.amount);
+        write_action(out, action, street_bet, *stack == 0)?;
+        street_bet = street_bet.max(action.total_bet);
+    }
+    if phase.is_none() {
+        write_dealt(out, h, hero)?;
+    }
+
+    match h.boards.len() {
+        0 | 1 => {
+            write_street(out, &RoundPhase::River, &board, shown_cards, None)?;
+        }
+        _ => {
+            for (i, runout) in h.boards.iter().enumerate() {
+                write_street(out, &RoundPhase::River, runout, shown_cards, ORDINALS.get(i).copied())?;
+            }
+        }
+    }
+
+    write_showdown(out, h)?;
+    write_summary(out, h)
+}
+
+fn hand_number(h: &HandHistory) -> u64 {
+    Uuid::parse_str(&h.hand_id).map(|id| id.as_u64_pair().0 >> 1).unwrap_or(h.hand_number)
+}
+
+fn game_name(variant: GameVariant) -> &'static str {
+    match variant {
+        GameVariant::Holdem => "Hold'em",
+        GameVariant::FiveCardDraw => "5 Card Draw",
+        GameVariant::TripleDraw27 => "Triple Draw 2-7 Lowball",
+    }
+}
+
+fn limit_name(structure: BettingStructure) -> &'static str {
+    match structure {
+        BettingStructure::NoLimit => "No Limit",
+        BettingStructure::PotLimit => "Pot Limit",
+        BettingStructure::FixedLimit => "Limit",
+    }
+}
+
+fn cards(cards: &[Card]) -> String {
+    cards.iter().map(Card::to_string).collect::<Vec<_>>().join(" ")
+}
+
+fn write_dealt(out: &mut String, h: &HandHistory, hero: Option<&str>) -> std::fmt::Result {
+    let header = if h.variant.uses_board() { "HOLE CARDS" } else { "DEALING HANDS" };
+    writeln!(out, "*** {header} ***")?;
+
+    if let Some(seat) = h.seats.iter().find(|s| Some(s.player_id.as_str()) == hero) {
+        writeln!(out, "Dealt to {} [{}]", seat.player_id, cards(&seat.cards))?;
+    }
+    Ok(())
+}
+
+/// Writes the headers for every street up to `phase` not shown yet; returns the board cards shown.
+fn write_street(
+    out: &mut String,
+    phase: &RoundPhase,
+    board: &[Card],
+    shown: usize,
+    ordinal: Option<&str>,
+) -> Result<usize, std::fmt::Error> {
+    let prefix = ordinal.map(|o| format!("{o} ")).unwrap_or_default();
+
+    match phase {
+        RoundPhase::Draw { round } => {
+            let ordinal = ORDINALS.get(usize::from(*round).saturating_sub(1)).unwrap_or(&"FINAL");
+            writeln!(out, "*** {ordinal} DRAW ***")?;
+            return Ok(shown);
+        }
+        RoundPhase::Flop | RoundPhase::Turn | RoundPhase::River => {}
+        _ => return Ok(shown),
+    }
+
+    let target = match phase {
+        RoundPhase::Flop => 3,
+        RoundPhase::Turn => 4,
+        _ => 5,
+    }
+    .min(board.len());
+
+    let mut dealt = shown;
+    while dealt < target {
+        let next = if dealt < 3 { 3.min(target) } else { dealt + 1 };
+        let street = match next {
+            3 => "FLOP",
+            4 => "TURN",
+            _ => "RIVER",
+        };
+        match dealt {
+            0 => writeln!(out, "*** {prefix}{street} *** [{}]", cards(&board[..next]))?,
+            _ => writeln!(out, "*** {prefix}{street} *** [{}] [{}]", cards(&board[..dealt]), cards(&board[dealt..next]))?,
+        }
+        dealt = next;
+    }
+    Ok(if ordinal.is_some() { shown } else { dealt })
+}
+
+fn write_action(out: &mut String, a: &ActionRecord, street_bet: u64, all_in: bool) -> std::fmt::Result {
+    let player = &a.player_id;
+    let all_in = if all_in && a.amount > 0 { " and is all-in" } else { "" };
+
+    match &a.action {
+        RecordedAction::Ante => writeln!(out, "{player}: posts the ante {}", a.amount),
+        RecordedAction::SmallBlind => writeln!(out, "{player}: posts small blind {}", a.amount),
+        RecordedAction::BigBlind => writeln!(out, "{player}: posts big blind {}", a.amount),
+        RecordedAction::Fold => writeln!(out, "{player}: folds"),
+        RecordedAction::Check => writeln!(out, "{player}: checks"),
+        RecordedAction::Draw { discarded: 0 } => writeln!(out, "{player}: stands pat"),
+        RecordedAction::Draw { discarded } => writeln!(out, "{player}: discards {discarded} card{}", plural(*discarded)),
+        RecordedAction::RunBoards { boards } => writeln!(out, "{player}: chooses to run it {boards} time{}", plural(usize::from(*boards))),
+        _ if a.total_bet <= street_bet => writeln!(out, "{player}: calls {}{all_in}", a.amount),
+        _ if street_bet == 0 => writeln!(out, "{player}: bets {}{all_in}", a.amount),
+        _ => writeln!(out, "{player}: raises {} to {}{all_in}", a.total_bet - street_bet, a.total_bet),
+    }
+}
+
+fn plural(count: usize) -> &'static str {
+    if count == 1 { "" } else { "s" }
+}
+
+fn write_showdown(out: &mut String, h: &HandHistory) -> std::fmt::Result {
+    if !h.showdown.is_empty() {
+        writeln!(out, "*** SHOW DOWN ***")?;
+        for shown in h.showdown.iter().filter(|r| r.board == 0) {
+            writeln!(out, "{}: shows [{}] ({})", player_at(h, shown.seat), cards(&shown.hand), shown.description)?;
+        }
+    }
+
+    let side_pots = h.winners.iter().any(|w| w.pot_index > 0);
+    for award in &h.winners {
+        let pot = match (side_pots, award.pot_index) {
+            (false, _) => "pot".to_string(),
+            (true, 0) => "main pot".to_string(),
+            (true, i) => format!("side pot-{i}"),
+        };
+        writeln!(out, "{} collected {} from {pot}", player_at(h, award.seat), award.amount)?;
+    }
+    Ok(())
+}
+
+fn write_summary(out: &mut String, h: &HandHistory) -> std::fmt::Result {
+    let total: u64 = h.winners.iter().map(|w| w.amount).sum::<u64>() + h.rake;
+
+    writeln!(out, "*** SUMMARY ***")?;
+    writeln!(out, "Total pot {total} | Rake {}", h.rake)?;
+    match h.boards.as_slice() {
+        [] => {}
+        [board] if board.is_empty() => {}
+        [board] => writeln!(out, "Board [{}]", cards(board))?,
+        boards => {
+            for (i, board) in boards.iter().enumerate() {
+                writeln!(out, "{} Board [{}]", ORDINALS.get(i).unwrap_or(&""), cards(board))?;
+            }
+        }
+    }
+
+    for seat in &h.seats {
+        let role = role(h, seat.seat);
+        let won: u64 = h.winners.iter().filter(|w| w.seat == seat.seat).map(|w| w.amount).sum();
+        let shown = h.showdown.iter().any(|r| r.seat == seat.seat);
+        let folded = h.actions
+            .iter()
+            .find(|a| a.seat == seat.seat && matches!(a.action, RecordedAction::Fold));
+
+        let outcome = match (folded, shown, won) {
+            (Some(fold), _, _) => format!("folded {}", street_name(&fold.phase)),
+            (None, true, 0) => format!("showed [{}] and lost", cards(&seat.cards)),
+            (None, true, won) => format!("showed [{}] and won ({won})", cards(&seat.cards)),
+            (None, false, 0) => "mucked".to_string(),
+            (None, false, won) => format!("collected ({won})"),
+        };
+        writeln!(out, "Seat {}: {}{role} {outcome}", seat.seat + 1, seat.player_id)?;
+    }
+    Ok(())
+}
+
+fn player_at(h: &HandHistory, seat: usize) -> &str {
+    h.seats.iter().find(|s| s.seat == seat).map_or("", |s| s.player_id.as_str())
+}
+
+fn role(h: &HandHistory, seat: usize) -> &'static str {
+    let posted = |kind: fn(&RecordedAction) -> bool| h.actions.iter().any(|a| a.seat == seat && kind(&a.action));
+
+    if seat == h.button_seat {
+        " (button)"
+    } else if posted(|a| matches!(a, RecordedAction::SmallBlind)) {
+        " (small blind)"
+    } else if posted(|a| matches!(a, RecordedAction::BigBlind)) {
+        " (big blind)"
+    } else {
+        ""
+    }
+}
+
+fn street_name(phase: &RoundPhase) -> &'static str {
+    match phase {
+        RoundPhase::Preflop => "before Flop",
+        RoundPhase::Flop => "on the Flop",
+        RoundPhase::Turn => "on the Turn",
+        RoundPhase::River => "on the River",
+        RoundPhase::Predraw => "before the Draw",
+        _ => "after the Draw",
+    }
+}
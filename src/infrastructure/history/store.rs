@@ -0,0 +1,147 @@
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use chrono::{DateTime, Utc};
+use tokio::fs::OpenOptions;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+use crate::domain::HandHistory;
+use crate::infrastructure::Flush;
+
+/// Hand histories kept as JSON lines in an append-only file, with an index of where each
+/// hand, player and table's lines are so a query reads only those.
+#[derive(Clone)]
+pub struct HandHistoryStore {
+    path: Arc<PathBuf>,
+    index: Arc<RwLock<Index>>,
+}
+
+/// Byte ranges of lines in the file, and which lines belong to whom.
+#[derive(Default)]
+struct Index {
+    lines: Vec<(u64, usize)>,
+    hands: HashMap<String, usize>,
+    players: HashMap<String, Vec<usize>>,
+    tables: HashMap<String, Vec<usize>>,
+    end: u64,
+}
+
+impl Index {
+    fn add(&mut self, history: &HandHistory, len: usize) {
+        let line = self.lines.len();
+        self.lines.push((self.end, len));
+        self.end += len as u64;
+
+        self.hands.insert(history.hand_id.clone(), line);
+        self.tables.entry(history.table_id.clone()).or_default().push(line);
+        for seat in &history.seats {
+            self.players.entry(seat.player_id.clone()).or_default().push(line);
+        }
+    }
+}
+
+impl HandHistoryStore {
+    /// Reads the file once to build the index; a missing file is an empty store.
+    pub fn open(path: impl Into<PathBuf>) -> anyhow::Result<Self> {
+        let path = path.into();
+        let raw = match std::fs::read_to_string(&path) {
+            Ok(raw) => raw,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut index = Index::default();
+        for line in raw.split_inclusive('\n') {
+            match serde_json::from_str::<HandHistory>(line) {
+                Ok(history) => index.add(&history, line.len()),
+                Err(e) => {
+                    if !line.trim().is_empty() {
+                        tracing::warn!(offset = index.end, "skipping unreadable hand history: {e}");
+                    }
+                    index.end += line.len() as u64;
+                }
+            }
+        }
+        Ok(Self { path: Arc::new(path), index: Arc::new(RwLock::new(index)) })
+    }
+
+    /// Spawns the writer; every history sent is on disk before the next one is taken.
+    pub fn start_writer(&self, mut flush: Flush) -> (mpsc::UnboundedSender<HandHistory>, JoinHandle<()>) {
+        let (tx, mut rx) = mpsc::unbounded_channel::<HandHistory>();
+        let (path, index) = (self.path.clone(), self.index.clone());
+
+        let writer = tokio::spawn(async move {
+            while let Some(history) = flush.next(&mut rx).await {
+                match append(&path, &history).await {
+                    Ok(len) => index.write().expect("history index poisoned").add(&history, len),
+                    Err(e) => tracing::error!(hand_id = %history.hand_id, "could not write hand history: {e}"),
+                }
+            }
+        });
+
+        (tx, writer)
+    }
+
+    pub async fn for_player(&self, player_id: &str) -> anyhow::Result<Vec<HandHistory>> {
+        self.read(|index| index.players.get(player_id).cloned().unwrap_or_default()).await
+    }
+
+    /// A player's hands that started within `[from, to)`; open ends are unbounded.
+    pub async fn for_player_between(
+        &self,
+        player_id: &str,
+        from: Option<DateTime<Utc>>,
+        to: Option<DateTime<Utc>>,
+    ) -> anyhow::Result<Vec<HandHistory>> {
+        let mut hands = self.for_player(player_id).await?;
+        hands.retain(|h| from.is_none_or(|from| h.started_at >= from) && to.is_none_or(|to| h.started_at < to));
+        Ok(hands)
+    }
+
+    pub async fn for_table(&self, table_id: &str) -> anyhow::Result<Vec<HandHistory>> {
+        self.read(|index| index.tables.get(table_id).cloned().unwrap_or_default()).await
+    }
+
+    pub async fn hand(&self, hand_id: &str) -> anyhow::Result<Option<HandHistory>> {
+        Ok(self.read(|index| index.hands.get(hand_id).copied().into_iter().collect()).await?.pop())
+    }
+
+    pub async fn all(&self) -> anyhow::Result<Vec<HandHistory>> {
+        self.read(|index| (0..index.lines.len()).collect()).await
+    }
+
+    /// Reads the lines `select` picks out of the index, in file order.
+    async fn read(&self, select: impl FnOnce(&Index) -> Vec<usize>) -> anyhow::Result<Vec<HandHistory>> {
+        let ranges: Vec<(u64, usize)> = {
+            let index = self.index.read().expect("history index poisoned");
+            select(&index).into_iter().map(|line| index.lines[line]).collect()
+        };
+        if ranges.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut file = tokio::fs::File::open(self.path.as_ref()).await?;
+        let mut histories = Vec::with_capacity(ranges.len());
+        for (offset, len) in ranges {
+            let mut line = vec![0; len];
+            file.seek(SeekFrom::Start(offset)).await?;
+            file.read_exact(&mut line).await?;
+            histories.push(serde_json::from_slice(&line)?);
+        }
+        Ok(histories)
+    }
+}
+
+/// Returns the length of the line written.
+async fn append(path: &PathBuf, history: &HandHistory) -> anyhow::Result<usize> {
+    let mut line = serde_json::to_string(history)?;
+    line.push('\n');
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path).await?;
+    file.write_all(line.as_bytes()).await?;
+    file.sync_data().await?;
+    Ok(line.len())
+}
@@ -0,0 +1,4 @@
+pub mod store;
+pub mod reconcile;
+
+pub use reconcile::{reconcile, start_reconciler};
@@ -0,0 +1,64 @@
+use std::time::Duration;
+use chrono::Utc;
+use tokio::sync::{mpsc, oneshot};
+
+use crate::domain::{GameCommand, ReconciliationReport};
+use crate::game::TableRegistry;
+use crate::infrastructure::AccountStore;
+use crate::shared::RECONCILE_INTERVAL_SECS;
+
+/// Checks every table's chips against the ledger and the ledger against itself.
+pub async fn reconcile(tables: &[(String, mpsc::Sender<GameCommand>)], accounts: &AccountStore) -> ReconciliationReport {
+    let mut report = ReconciliationReport {
+        checked_at: Utc::now(),
+        tables: vec![],
+        unreachable_tables: vec![],
+        unbalanced_journals: vec![],
+    };
+
+    for (table_id, commands) in tables {
+        let (tx, rx) = oneshot::channel();
+        let checked = match commands.send(GameCommand::Reconcile { reply: tx }).await {
+            Ok(()) => rx.await.ok(),
+            Err(_) => None,
+        };
+        match checked {
+            Some(reconciliation) => report.tables.push(reconciliation),
+            None => report.unreachable_tables.push(table_id.clone()),
+        }
+    }
+
+    match accounts.unbalanced_journals().await {
+        Ok(journals) => report.unbalanced_journals = journals,
+        Err(e) => tracing::error!("could not check journal balances: {e}"),
+    }
+    report
+}
+
+pub fn start_reconciler(tables: TableRegistry, accounts: AccountStore) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(RECONCILE_INTERVAL_SECS));
+        loop {
+            interval.tick().await;
+            let report = reconcile(&tables.command_senders(), &accounts).await;
+            if report.is_clean() { continue; }
+
+            for table in report.tables.iter().filter(|t| t.discrepancy != 0) {
+                tracing::warn!(
+                    table_id = %table.table_id,
+                    discrepancy = table.discrepancy,
+                    on_table = table.stacks + table.pot,
+                    net_buy_ins = table.net_buy_ins,
+                    rake = table.rake,
+                    "chips on table do not match the ledger",
+                );
+            }
+            if !report.unreachable_tables.is_empty() {
+                tracing::warn!(tables = ?report.unreachable_tables, "tables did not answer reconciliation");
+            }
+            if !report.unbalanced_journals.is_empty() {
+                tracing::warn!(journals = ?report.unbalanced_journals, "unbalanced journals in the ledger");
+            }
+        }
+    });
+}
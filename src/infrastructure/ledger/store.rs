@@ -0,0 +1,114 @@
+use rusqlite::{Connection, params};
+
+use crate::domain::{JournalEntry, JournalKind, LedgerAccount, TableReconciliation};
+
+pub const SCHEMA: &str = "
+    CREATE TABLE IF NOT EXISTS journals (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        table_id TEXT NOT NULL,
+        hand_id TEXT,
+        kind TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        memo TEXT
+    );
+    CREATE TABLE IF NOT EXISTS postings (
+        journal_id INTEGER NOT NULL REFERENCES journals(id),
+        account TEXT NOT NULL,
+        amount INTEGER NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS postings_account ON postings (account);
+";
+
+/// Adds the columns a ledger written by an older server lacks.
+pub fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let has_memo: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('journals') WHERE name = 'memo'",
+        [],
+        |row| row.get(0),
+    )?;
+    if !has_memo {
+        conn.execute_batch("ALTER TABLE journals ADD COLUMN memo TEXT")?;
+    }
+    Ok(())
+}
+
+/// Writes a journal entry; run it inside the transaction that moves the chips it describes.
+pub fn post(conn: &Connection, entry: &JournalEntry) -> Result<(), String> {
+    if !entry.is_balanced() {
+        return Err(format!("unbalanced {} journal on table {}", entry.kind.as_str(), entry.table_id));
+    }
+
+    conn.execute(
+        "INSERT INTO journals (table_id, hand_id, kind, created_at, memo) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![entry.table_id, entry.hand_id, entry.kind.as_str(), entry.at.to_rfc3339(), entry.memo],
+    )
+    .map_err(|e| e.to_string())?;
+    let journal_id = conn.last_insert_rowid();
+
+    for posting in entry.postings.iter().filter(|p| p.amount != 0) {
+        conn.execute(
+            "INSERT INTO postings (journal_id, account, amount) VALUES (?1, ?2, ?3)",
+            params![journal_id, posting.account, posting.amount],
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+/// Balances of every account on a table that still holds chips.
+pub fn table_balances(conn: &Connection, table_id: &str) -> Result<Vec<(String, i64)>, String> {
+    let prefix = LedgerAccount::table_prefix(table_id);
+    let mut statement = conn
+        .prepare(
+            "SELECT account, SUM(amount) FROM postings WHERE substr(account, 1, length(?1)) = ?1
+             GROUP BY account HAVING SUM(amount) != 0",
+        )
+        .map_err(|e| e.to_string())?;
+    let rows = statement
+        .query_map(params![prefix], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+/// Compares a table's chips with what the ledger says went on and off it.
+pub fn reconcile_table(conn: &Connection, table_id: &str, stacks: u64, pot: u64) -> Result<TableReconciliation, String> {
+    let prefix = LedgerAccount::table_prefix(table_id);
+    let mut statement = conn
+        .prepare(
+            "SELECT j.kind, SUM(p.amount) FROM postings p JOIN journals j ON j.id = p.journal_id
+             WHERE substr(p.account, 1, length(?1)) = ?1 GROUP BY j.kind",
+        )
+        .map_err(|e| e.to_string())?;
+    let flows = statement
+        .query_map(params![prefix], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let (mut net_buy_ins, mut rake) = (0, 0);
+    for (kind, amount) in flows {
+        let kind: JournalKind = serde_json::from_value(serde_json::Value::String(kind)).map_err(|e| e.to_string())?;
+        match kind {
+            JournalKind::Rake => rake -= amount,
+            kind if kind.crosses_table() => net_buy_ins += amount,
+            _ => {}
+        }
+    }
+
+    Ok(TableReconciliation {
+        table_id: table_id.to_string(),
+        stacks,
+        pot,
+        net_buy_ins,
+        rake,
+        discrepancy: (stacks + pot) as i64 - (net_buy_ins - rake),
+    })
+}
+
+pub fn unbalanced_journals(conn: &Connection) -> Result<Vec<i64>, String> {
+    let mut statement = conn
+        .prepare("SELECT journal_id FROM postings GROUP BY journal_id HAVING SUM(amount) != 0")
+        .map_err(|e| e.to_string())?;
+    let rows = statement.query_map([], |row| row.get(0)).map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
@@ -0,0 +1,69 @@
+use prometheus::{
+    Histogram, HistogramOpts, IntCounter, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry, TextEncoder,
+};
+
+use crate::shared::HAND_DURATION_BUCKETS_SECS;
+
+/// Handles to the server's Prometheus metrics. Clones share the same values.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    pub connected_sockets: IntGauge,
+    pub active_tables: IntGauge,
+    pub hands_dealt: IntCounter,
+    pub actions: IntCounterVec,
+    pub broadcast_lags: IntCounter,
+    pub skipped_events: IntCounter,
+    pub command_queue_depth: IntGaugeVec,
+    pub hand_duration: Histogram,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        let registry = Registry::new();
+        let metrics = Self {
+            connected_sockets: IntGauge::new("ws_connected_sockets", "Open WebSocket connections").unwrap(),
+            active_tables: IntGauge::new("tables_active", "Table actors running, tournament tables included").unwrap(),
+            hands_dealt: IntCounter::new("hands_dealt_total", "Hands dealt").unwrap(),
+            actions: IntCounterVec::new(Opts::new("actions_total", "Actions taken, blinds and antes included"), &["action"])
+                .unwrap(),
+            broadcast_lags: IntCounter::new("ws_broadcast_lags_total", "Times a socket fell behind its table's events")
+                .unwrap(),
+            skipped_events: IntCounter::new("ws_skipped_events_total", "Events dropped for sockets that fell behind")
+                .unwrap(),
+            command_queue_depth: IntGaugeVec::new(
+                Opts::new("table_command_queue_depth", "Commands waiting for a table's actor"),
+                &["table_id"],
+            )
+            .unwrap(),
+            hand_duration: Histogram::with_opts(
+                HistogramOpts::new("hand_duration_seconds", "Time from the deal to the end of a hand")
+                    .buckets(HAND_DURATION_BUCKETS_SECS.to_vec()),
+            )
+            .unwrap(),
+            registry,
+        };
+
+        let collectors: [Box<dyn prometheus::core::Collector>; 8] = [
+            Box::new(metrics.connected_sockets.clone()),
+            Box::new(metrics.active_tables.clone()),
+            Box::new(metrics.hands_dealt.clone()),
+            Box::new(metrics.actions.clone()),
+            Box::new(metrics.broadcast_lags.clone()),
+            Box::new(metrics.skipped_events.clone()),
+            Box::new(metrics.command_queue_depth.clone()),
+            Box::new(metrics.hand_duration.clone()),
+        ];
+        for collector in collectors {
+            metrics.registry.register(collector).expect("metric names are unique");
+        }
+        metrics
+    }
+}
+
+impl Metrics {
+    /// Every metric in the Prometheus text format.
+    pub fn render(&self) -> String {
+        TextEncoder::new().encode_to_string(&self.registry.gather()).unwrap_or_default()
+    }
+}
@@ -0,0 +1,3 @@
+pub mod exporter;
+
+pub use exporter::Metrics;
@@ -1,3 +1,15 @@
 pub mod web_socket;
+pub mod history;
+pub mod game_log;
+pub mod accounts;
+pub mod ledger;
+pub mod metrics;
+pub mod shutdown;
 
-pub use web_socket::ws_handler;
\ No newline at end of file
+pub use web_socket::{ws_handler, tournament_ws_handler, SeatRequest};
+pub use history::{HandHistoryStore, export_ohh, to_pokerstars};
+pub use game_log::GameLogStore;
+pub use accounts::{AccountStore, SessionTokens};
+pub use ledger::{reconcile, start_reconciler};
+pub use metrics::Metrics;
+pub use shutdown::Flush;
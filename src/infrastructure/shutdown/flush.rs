@@ -0,0 +1,26 @@
+use tokio::sync::{mpsc, watch};
+
+/// Tells the background writers to stop taking records, write out what is already queued and
+/// exit, so that nothing recorded before shutdown is lost.
+#[derive(Clone)]
+pub struct Flush(watch::Receiver<bool>);
+
+impl Flush {
+    /// The flush, and the switch that starts it: sending `true`, or dropping the switch.
+    pub fn new() -> (watch::Sender<bool>, Self) {
+        let (tx, rx) = watch::channel(false);
+        (tx, Self(rx))
+    }
+
+    /// The next queued record; `None` once the channel is closed and empty.
+    pub async fn next<T>(&mut self, rx: &mut mpsc::UnboundedReceiver<T>) -> Option<T> {
+        if !*self.0.borrow() {
+            tokio::select! {
+                record = rx.recv() => return record,
+                _ = self.0.changed() => {}
+            }
+        }
+        rx.close();
+        rx.recv().await
+    }
+}
@@ -0,0 +1,3 @@
+pub mod flush;
+
+pub use flush::Flush;
@@ -1 +0,0 @@
-pub(super) const INITIAL_HAND_SIZE: usize = 2;
@@ -1,62 +1,314 @@
 use axum::{
     extract::ws::{Message, WebSocket, WebSocketUpgrade},
-    response::IntoResponse,
+    http::StatusCode,
+    response::Response,
 };
 use tokio::sync::{mpsc, broadcast, oneshot};
+use schemars::JsonSchema;
 use serde::Deserialize;
 
-use crate::domain::{ServerEvent, PlayerAction, GameCommand};
+use crate::domain::{
+    ClientEvent, ClientRequest, ServerEvent, PlayerAction, GameCommand, DirectorCommand, Purchase, TableHandle,
+    TournamentEvent
+};
+use crate::game::{RegisteredTable, TableRegistry};
+use crate::infrastructure::{AccountStore, Metrics, SessionTokens};
+use crate::shared::{DEFAULT_BUY_IN, MAIN_TABLE_ID};
+
+/// Browsers cannot set headers on a WebSocket upgrade, so the session token rides in the query.
+#[derive(Deserialize, JsonSchema)]
+pub struct SeatRequest {
+    token: Option<String>,
+    table_id: Option<String>,
+    buy_in: Option<u64>,
+}
+
+fn authorize(tokens: &SessionTokens, token: Option<&str>) -> Result<String, String> {
+    tokens.verify(token.ok_or("missing session token")?)
+}
 
 pub async fn ws_handler(
     ws: WebSocketUpgrade,
-    manager_tx: mpsc::Sender<GameCommand>,
-    broadcaster: broadcast::Sender<ServerEvent>,
-) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| handle_socket(socket, manager_tx, broadcaster))
+    request: SeatRequest,
+    tables: TableRegistry,
+    accounts: AccountStore,
+    tokens: SessionTokens,
+    metrics: Metrics,
+) -> Result<Response, (StatusCode, String)> {
+    let account_id = authorize(&tokens, request.token.as_deref()).map_err(|e| (StatusCode::UNAUTHORIZED, e))?;
+    let table_id = request.table_id.unwrap_or_else(|| MAIN_TABLE_ID.into());
+    let table = tables.get(&table_id).ok_or((StatusCode::NOT_FOUND, "no such table".to_string()))?;
+    let seat = match &table.config.sit_and_go {
+        Some(tournament) => Seat { account_id, table_id, buy_in: tournament.buy_in, entry_fee: true },
+        None => Seat { account_id, table_id, buy_in: request.buy_in.unwrap_or(DEFAULT_BUY_IN), entry_fee: false },
+    };
+
+    Ok(ws.on_upgrade(move |socket| handle_socket(socket, table, accounts, seat, metrics)))
+}
+
+/// Who is sitting down where, and for how much.
+struct Seat {
+    account_id: String,
+    table_id: String,
+    buy_in: u64,
+    /// A sit-and-go entry: the buy-in goes to the prize pool rather than onto the table.
+    entry_fee: bool,
+}
+
+pub async fn tournament_ws_handler(
+    ws: WebSocketUpgrade,
+    request: SeatRequest,
+    director_tx: mpsc::Sender<DirectorCommand>,
+    director_events: broadcast::Sender<TournamentEvent>,
+    tokens: SessionTokens,
+    metrics: Metrics,
+) -> Result<Response, (StatusCode, String)> {
+    let account_id = authorize(&tokens, request.token.as_deref()).map_err(|e| (StatusCode::UNAUTHORIZED, e))?;
+
+    Ok(ws.on_upgrade(move |socket| handle_tournament_socket(socket, director_tx, director_events, account_id, metrics)))
+}
+
+/// Counts a socket as connected for as long as it is held.
+struct Connection(Metrics);
+
+impl Connection {
+    fn open(metrics: Metrics) -> Self {
+        metrics.connected_sockets.inc();
+        Self(metrics)
+    }
+
+    fn lagged(&self, skipped: u64) {
+        self.0.broadcast_lags.inc();
+        self.0.skipped_events.inc_by(skipped);
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.0.connected_sockets.dec();
+    }
 }
 
 async fn handle_socket(
     mut socket: WebSocket,
-    manager_tx: mpsc::Sender<GameCommand>,
-    broadcaster: broadcast::Sender<ServerEvent>,
+    table: RegisteredTable,
+    accounts: AccountStore,
+    seat: Seat,
+    metrics: Metrics,
 ) {
-    let mut events = broadcaster.subscribe();
+    let connection = Connection::open(metrics);
+    let manager_tx = table.commands;
+    let mut events = table.events.subscribe();
+    let table_id = seat.table_id.clone();
+
+    let Some(player_id) = join_table(&mut socket, &manager_tx, &accounts, seat).await else {
+        return;
+    };
+
+    tracing::info!("[WS] connected {}", player_id);
+
+    loop {
+        tokio::select! {
+            ev = events.recv() => {
+                match ev {
+                    Ok(server_event) => {
+                        if !forward_event(&mut socket, &manager_tx, &player_id, server_event).await {
+                            break;
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        connection.lagged(skipped);
+                        tracing::warn!("[WS] {} lagged on events", player_id);
+                    }
+                    Err(broadcast::error::RecvError::Closed) => {
+                        break;
+                    }
+                }
+            }
+
+            msg = socket.recv() => {
+                let purchase = match (&msg, &table.config.sit_and_go) {
+                    (Some(Ok(Message::Text(text))), Some(tournament)) => match parse_request(text) {
+                        Some(ClientRequest::Purchase(purchase)) => Some((purchase, tournament.price(purchase))),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                let open = match purchase {
+                    Some((purchase, price)) => {
+                        buy(&mut socket, &manager_tx, &accounts, &table_id, &player_id, purchase, price).await
+                    }
+                    None => handle_client_message(&mut socket, &manager_tx, &player_id, msg).await,
+                };
+                if !open {
+                    break;
+                }
+            }
+        }
+    }
+
+    tracing::info!("[WS] disconnected {}", player_id);
+}
+
+/// Pays for a rebuy, add-on or re-entry from the bankroll and refunds it if the table refuses.
+async fn buy(
+    socket: &mut WebSocket,
+    manager_tx: &mpsc::Sender<GameCommand>,
+    accounts: &AccountStore,
+    table_id: &str,
+    player_id: &str,
+    purchase: Purchase,
+    price: u64,
+) -> bool {
+    if let Err(message) = accounts.enter_tournament(player_id, table_id, price).await {
+        return send_event(socket, &ServerEvent::Error { message }).await;
+    }
+
+    let (tx, rx) = oneshot::channel();
+    let command = GameCommand::Purchase { player_id: player_id.to_string(), purchase, reply: tx };
+    let bought = match manager_tx.send(command).await {
+        Ok(()) => rx.await.unwrap_or_else(|_| Err("table is closed".into())),
+        Err(_) => Err("table is closed".into()),
+    };
+    let Err(message) = bought else { return true; };
+
+    if let Err(e) = accounts.refund_entry(player_id, table_id, price).await {
+        tracing::error!(%player_id, "could not refund purchase: {e}");
+    }
+    send_event(socket, &ServerEvent::Error { message }).await
+}
+
+/// Takes back a seat the account already holds, or buys it in from its bankroll before taking a
+/// new one and refunds it if the table turns the player away.
+async fn join_table(
+    socket: &mut WebSocket,
+    manager_tx: &mpsc::Sender<GameCommand>,
+    accounts: &AccountStore,
+    Seat { account_id, table_id, buy_in, entry_fee }: Seat,
+) -> Option<String> {
+    let (tx, rx) = oneshot::channel();
+    let command = GameCommand::Reconnect { player_id: account_id.clone(), reply: tx };
+    let reconnected = match manager_tx.send(command).await {
+        Ok(()) => rx.await.unwrap_or_else(|_| Err("table is closed".into())),
+        Err(_) => Err("table is closed".into()),
+    };
+    match reconnected {
+        Ok(true) => return catch_up(socket, manager_tx, &account_id).await.then_some(account_id),
+        Ok(false) => {}
+        Err(message) => {
+            let _ = send_event(socket, &ServerEvent::Error { message }).await;
+            return None;
+        }
+    }
+
+    let paid = match entry_fee {
+        true => accounts.enter_tournament(&account_id, &table_id, buy_in).await,
+        false => accounts.buy_in(&account_id, &table_id, buy_in).await,
+    };
+    if let Err(message) = paid {
+        let _ = send_event(socket, &ServerEvent::Error { message }).await;
+        return None;
+    }
 
     let (join_tx, join_rx) = oneshot::channel();
-    if manager_tx.send(GameCommand::Join { reply: join_tx }).await.is_err() {
+    let command = GameCommand::Join { account_id: account_id.clone(), stack: buy_in, reply: join_tx };
+    let joined = match manager_tx.send(command).await {
+        Ok(()) => join_rx.await.unwrap_or_else(|_| Err("table is closed".into())),
+        Err(_) => Err("table is closed".into()),
+    };
+
+    match joined {
+        Ok(player_id) => Some(player_id),
+        Err(message) => {
+            let refunded = match entry_fee {
+                true => accounts.refund_entry(&account_id, &table_id, buy_in).await,
+                false => accounts.refund_buy_in(&account_id, &table_id, buy_in).await,
+            };
+            if let Err(e) = refunded {
+                tracing::error!(%account_id, "could not refund buy-in: {e}");
+            }
+            let _ = send_event(socket, &ServerEvent::Error { message }).await;
+            None
+        }
+    }
+}
+
+/// Tournament players are moved between tables by the director, so the session follows
+/// `PlayerSeated` events and resubscribes to whichever table currently holds the player.
+async fn handle_tournament_socket(
+    mut socket: WebSocket,
+    director_tx: mpsc::Sender<DirectorCommand>,
+    director_events: broadcast::Sender<TournamentEvent>,
+    account_id: String,
+    metrics: Metrics,
+) {
+    let connection = Connection::open(metrics);
+    let mut tournament_events = director_events.subscribe();
+
+    let (register_tx, register_rx) = oneshot::channel();
+    if director_tx.send(DirectorCommand::Register { player_id: account_id, reply: register_tx }).await.is_err() {
         return;
     }
 
-    let player_id = match join_rx.await {
+    let player_id = match register_rx.await {
         Ok(Ok(id)) => id,
-        _ => return,
+        Ok(Err(message)) => {
+            let _ = send_event(&mut socket, &ServerEvent::Error { message }).await;
+            return;
+        }
+        Err(_) => return,
     };
 
-    tracing::info!("[WS] connected {}", player_id);
+    tracing::info!("[WS] registered {}", player_id);
+
+    // A player coming back mid-tournament picks up the seat the director already gave them.
+    let mut table = locate(&director_tx, &player_id).await;
+    let mut table_events = table.as_ref().map(|t| t.events.subscribe());
+    if let Some(table) = &table {
+        let (tx, rx) = oneshot::channel();
+        let command = GameCommand::Reconnect { player_id: player_id.clone(), reply: tx };
+        let reconnected = match table.commands.send(command).await {
+            Ok(()) => rx.await.unwrap_or_else(|_| Err("table is closed".into())),
+            Err(_) => Err("table is closed".into()),
+        };
+        if let Err(message) = reconnected {
+            let _ = send_event(&mut socket, &ServerEvent::Error { message }).await;
+            return;
+        }
+        if !catch_up(&mut socket, &table.commands, &player_id).await {
+            return;
+        }
+    }
 
     loop {
         tokio::select! {
-            ev = events.recv() => {
+            ev = tournament_events.recv() => {
                 match ev {
-                    Ok(server_event) => {
-                        if socket.send(Message::Text(serde_json::to_string(&server_event).unwrap())).await.is_err() {
+                    Ok(event) => {
+                        let seated_here = matches!(
+                            &event,
+                            TournamentEvent::PlayerSeated { player_id: seated, .. } if *seated == player_id
+                        );
+
+                        if !send_event(&mut socket, &ServerEvent::Tournament(event)).await {
                             break;
                         }
 
-                        if let ServerEvent::RoundStarted = server_event {
-                            let (p_tx, p_rx) = oneshot::channel();
-                            let _ = manager_tx.send(GameCommand::GetPrivateState { player_id: player_id.clone(), reply: p_tx }).await;
-                            if let Ok(private) = p_rx.await {
-                                if private.hand.is_some() {
-                                    if socket.send(Message::Text(serde_json::to_string(&ServerEvent::PrivateState(private)).unwrap())).await.is_err() {
-                                        break;
-                                    }
+                        if seated_here {
+                            table = locate(&director_tx, &player_id).await;
+                            table_events = table.as_ref().map(|t| t.events.subscribe());
+
+                            if let Some(table) = &table {
+                                tracing::info!("[WS] {} seated at table {}", player_id, table.table_id);
+                                if !catch_up(&mut socket, &table.commands, &player_id).await {
+                                    break;
                                 }
                             }
                         }
                     }
-                    Err(broadcast::error::RecvError::Lagged(_)) => {
-                        tracing::warn!("[WS] {} lagged on events", player_id);
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        connection.lagged(skipped);
+                        tracing::warn!("[WS] {} lagged on tournament events", player_id);
                     }
                     Err(broadcast::error::RecvError::Closed) => {
                         break;
@@ -64,44 +316,49 @@ async fn handle_socket(
                 }
             }
 
-            msg = socket.recv() => {
-                match msg {
-                    Some(Ok(Message::Text(text))) => {
-                        #[derive(Deserialize)]
-                        struct ClientMsg {
-                            action: String,
-                            amount: Option<u64>,
+            ev = next_table_event(&mut table_events) => {
+                match (ev, &table) {
+                    (Ok(server_event), Some(table)) => {
+                        if !forward_event(&mut socket, &table.commands, &player_id, server_event).await {
+                            break;
                         }
-
-                        let Ok(cmd) = serde_json::from_str::<ClientMsg>(&text) else {
-                            continue;
-                        };
-
-                        let action = match cmd.action.as_str() {
-                            "fold" => PlayerAction::Fold,
-                            "check" => PlayerAction::Check,
-                            "call" => PlayerAction::Call,
-                            "bet" => PlayerAction::Bet { amount: cmd.amount.unwrap_or(0) },
-                            "raise" => PlayerAction::Raise { amount: cmd.amount.unwrap_or(0) },
-                            "allin" => PlayerAction::AllIn,
-                            _ => continue,
-                        };
-
-                        let (tx, rx) = oneshot::channel();
-                        let _ = manager_tx.send(GameCommand::Action { player_id: player_id.clone(), action, reply: tx }).await;
-                        let _ = rx.await;
                     }
-
-                    Some(Ok(Message::Close(_))) | None => {
-                        let _ = manager_tx.send(GameCommand::Disconnect { player_id: player_id.clone() }).await;
-                        break;
+                    (Err(broadcast::error::RecvError::Lagged(skipped)), _) => {
+                        connection.lagged(skipped);
+                        tracing::warn!("[WS] {} lagged on events", player_id);
                     }
+                    _ => table_events = None,
+                }
+            }
 
-                    Some(Ok(_)) => {}
-                    Some(Err(_)) => {
-                        let _ = manager_tx.send(GameCommand::Disconnect { player_id: player_id.clone() }).await;
-                        break;
+            msg = socket.recv() => {
+                let request = match &msg {
+                    Some(Ok(Message::Text(text))) => parse_request(text),
+                    _ => None,
+                };
+                let (tx, rx) = oneshot::channel();
+                let id = player_id.clone();
+                let cmd = match request {
+                    Some(ClientRequest::Purchase(purchase)) => Some(DirectorCommand::Purchase { player_id: id, purchase, reply: tx }),
+                    Some(ClientRequest::Deal(action)) => Some(DirectorCommand::Deal { player_id: id, action, reply: tx }),
+                    _ => None,
+                };
+                if let Some(cmd) = cmd {
+                    let _ = director_tx.send(cmd).await;
+                    if let Ok(Err(message)) = rx.await {
+                        if !send_event(&mut socket, &ServerEvent::Error { message }).await {
+                            break;
+                        }
                     }
+                    continue;
+                }
+
+                let open = match &table {
+                    Some(table) => handle_client_message(&mut socket, &table.commands, &player_id, msg).await,
+                    None => matches!(msg, Some(Ok(_))),
+                };
+                if !open {
+                    break;
                 }
             }
         }
@@ -109,3 +366,111 @@ async fn handle_socket(
 
     tracing::info!("[WS] disconnected {}", player_id);
 }
+
+async fn locate(director_tx: &mpsc::Sender<DirectorCommand>, player_id: &str) -> Option<TableHandle> {
+    let (tx, rx) = oneshot::channel();
+    director_tx.send(DirectorCommand::Locate { player_id: player_id.to_string(), reply: tx }).await.ok()?;
+    rx.await.ok().flatten()
+}
+
+/// Events sent before the socket subscribed are gone; catches up from a snapshot.
+async fn catch_up(socket: &mut WebSocket, manager_tx: &mpsc::Sender<GameCommand>, player_id: &str) -> bool {
+    let (tx, rx) = oneshot::channel();
+    let _ = manager_tx.send(GameCommand::GetState { reply: tx }).await;
+    if let Ok(state) = rx.await {
+        if !send_event(socket, &ServerEvent::GameState(state)).await {
+            return false;
+        }
+    }
+    send_private_state(socket, manager_tx, player_id).await
+}
+
+async fn next_table_event(
+    events: &mut Option<broadcast::Receiver<ServerEvent>>,
+) -> Result<ServerEvent, broadcast::error::RecvError> {
+    match events {
+        Some(events) => events.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+async fn forward_event(
+    socket: &mut WebSocket,
+    manager_tx: &mpsc::Sender<GameCommand>,
+    player_id: &str,
+    server_event: ServerEvent,
+) -> bool {
+    if !send_event(socket, &server_event).await {
+        return false;
+    }
+
+    match server_event {
+        ServerEvent::RoundStarted => send_private_state(socket, manager_tx, player_id).await,
+        // The table has already stood the player up, so there is no disconnect to report.
+        ServerEvent::PlayerRemoved { player_id: removed, .. } => removed != player_id,
+        _ => true,
+    }
+}
+
+/// Returns `false` once the connection is gone; the table is told about the disconnect.
+async fn handle_client_message(
+    socket: &mut WebSocket,
+    manager_tx: &mpsc::Sender<GameCommand>,
+    player_id: &str,
+    msg: Option<Result<Message, axum::Error>>,
+) -> bool {
+    match msg {
+        Some(Ok(Message::Text(text))) => {
+            let (tx, rx) = oneshot::channel();
+            let player_id = player_id.to_string();
+            let (cmd, is_draw) = match parse_request(&text) {
+                Some(ClientRequest::Action(action)) => {
+                    let is_draw = matches!(action, PlayerAction::Draw { .. });
+                    (GameCommand::Action { player_id: player_id.clone(), action, reply: tx }, is_draw)
+                }
+                Some(ClientRequest::Purchase(purchase)) => (GameCommand::Purchase { player_id: player_id.clone(), purchase, reply: tx }, false),
+                Some(ClientRequest::Deal(action)) => (GameCommand::Deal { player_id: player_id.clone(), action, reply: tx }, false),
+                Some(ClientRequest::ClientSeed(seed)) => (GameCommand::SetClientSeed { player_id: player_id.clone(), seed, reply: tx }, false),
+                None => return true,
+            };
+
+            let _ = manager_tx.send(cmd).await;
+            match rx.await {
+                Ok(Err(message)) => send_event(socket, &ServerEvent::Error { message }).await,
+                Ok(Ok(())) if is_draw => send_private_state(socket, manager_tx, &player_id).await,
+                _ => true,
+            }
+        }
+
+        Some(Ok(Message::Close(_))) | None | Some(Err(_)) => {
+            let _ = manager_tx.send(GameCommand::Disconnect { player_id: player_id.to_string() }).await;
+            false
+        }
+
+        Some(Ok(_)) => true,
+    }
+}
+
+fn parse_request(text: &str) -> Option<ClientRequest> {
+    serde_json::from_str::<ClientEvent>(text).ok().map(ClientRequest::from)
+}
+
+async fn send_event(socket: &mut WebSocket, event: &ServerEvent) -> bool {
+    socket.send(Message::Text(serde_json::to_string(event).unwrap())).await.is_ok()
+}
+
+async fn send_private_state(
+    socket: &mut WebSocket,
+    manager_tx: &mpsc::Sender<GameCommand>,
+    player_id: &str,
+) -> bool {
+    let (p_tx, p_rx) = oneshot::channel();
+    let _ = manager_tx.send(GameCommand::GetPrivateState { player_id: player_id.to_string(), reply: p_tx }).await;
+
+    match p_rx.await {
+        Ok(private) if private.hand.is_some() => {
+            send_event(socket, &ServerEvent::PrivateState(private)).await
+        }
+        _ => true,
+    }
+}
@@ -1,3 +1,3 @@
 pub mod handler;
 
-pub use handler::ws_handler;
+pub use handler::{ws_handler, tournament_ws_handler, SeatRequest};
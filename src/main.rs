@@ -4,12 +4,24 @@ mod domain;
 mod shared;
 mod game;
 
+use std::collections::HashSet;
 use std::net::SocketAddr;
-use tracing_subscriber;
+use std::sync::Arc;
+use std::time::Duration;
 use anyhow::Result;
+use tokio::sync::{mpsc, oneshot, watch};
 
-use crate::shared::{SERVER_ADDRESS, SERVER_PORT};
-use crate::game::GameManager;
+use crate::shared::{
+    ACCOUNTS_DB_PATH_ENV, ADMIN_ACCOUNTS_ENV, API_DOCS_COMMAND, DEFAULT_API_DOCS_DIR, DEFAULT_ACCOUNTS_DB_PATH, DEFAULT_GAME_LOG_DIR, DEFAULT_HAND_HISTORY_PATH,
+    EXPORT_OHH_COMMAND, GAME_LOG_DIR_ENV, GAME_VARIANT_ENV, HAND_HISTORY_PATH_ENV, MAIN_TABLE_ID, RECOVERY_POLICY_ENV,
+    REPLAY_LOG_COMMAND, SERVER_ADDRESS, SESSION_SECRET_ENV, SERVER_PORT, TABLE_CONFIG_ENV, TOURNAMENT_CONFIG_ENV,
+    TABLE_SEED_ENV, VERIFY_SHUFFLE_COMMAND, DRAIN_TIMEOUT_SECS,
+};
+use crate::game::{GameManager, RecoveryPolicy, TableRecorder, TableRegistry, TournamentDirector};
+use crate::domain::{DirectorCommand, GameCommand, GameVariant, LoggedCommand, MultiTableConfig, ShuffleProof, TableConfig};
+use crate::infrastructure::{
+    AccountStore, Flush, GameLogStore, HandHistoryStore, Metrics, SessionTokens, export_ohh, start_reconciler,
+};
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -17,9 +29,125 @@ async fn main() -> Result<()> {
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
-    let (manager_tx, broadcaster) = GameManager::start();
+    let history_path = std::env::var(HAND_HISTORY_PATH_ENV).unwrap_or_else(|_| DEFAULT_HAND_HISTORY_PATH.into());
+    let histories = HandHistoryStore::open(history_path)?;
 
-    let app = app::create_routes(manager_tx, broadcaster);
+    let log_dir = std::env::var(GAME_LOG_DIR_ENV).unwrap_or_else(|_| DEFAULT_GAME_LOG_DIR.into());
+    let game_logs = GameLogStore::new(log_dir);
+
+    let mut args = std::env::args().skip(1);
+    match args.next().as_deref() {
+        Some(EXPORT_OHH_COMMAND) => {
+            let out = args.next().ok_or_else(|| anyhow::anyhow!("usage: {EXPORT_OHH_COMMAND} <output-file>"))?;
+            let exported = export_ohh(&histories, &out).await?;
+            tracing::info!(exported, %out, "exported hand histories as OHH");
+            return Ok(());
+        }
+        Some(API_DOCS_COMMAND) => {
+            let dir = std::path::PathBuf::from(args.next().unwrap_or_else(|| DEFAULT_API_DOCS_DIR.into()));
+            std::fs::create_dir_all(&dir)?;
+            std::fs::write(dir.join("openapi.json"), app::docs::render(&app::docs::openapi()))?;
+            std::fs::write(dir.join("asyncapi.json"), app::docs::render(&app::docs::asyncapi()))?;
+            tracing::info!(dir = %dir.display(), "wrote API documents");
+            return Ok(());
+        }
+        Some(VERIFY_SHUFFLE_COMMAND) => {
+            let path = args.next().ok_or_else(|| anyhow::anyhow!("usage: {VERIFY_SHUFFLE_COMMAND} <proof.json>"))?;
+            let proof: ShuffleProof = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            game::fairness::verify(&proof).map_err(anyhow::Error::msg)?;
+            println!("shuffle verified: deck seed {}", proof.deck_seed);
+            return Ok(());
+        }
+        Some(REPLAY_LOG_COMMAND) => {
+            let table_id = args.next().unwrap_or_else(|| MAIN_TABLE_ID.into());
+            let entries = game_logs.read(&table_id).await?;
+            let snapshot = game_logs.read_snapshot(&table_id).await?;
+            let manager = GameManager::rebuild(snapshot, &entries).map_err(anyhow::Error::msg)?;
+            let rebuilt = serde_json::json!({ "state": manager.state, "hands": manager.cards.hands });
+            println!("{}", serde_json::to_string_pretty(&rebuilt)?);
+            return Ok(());
+        }
+        _ => {}
+    }
+
+    let accounts_path = std::env::var(ACCOUNTS_DB_PATH_ENV).unwrap_or_else(|_| DEFAULT_ACCOUNTS_DB_PATH.into());
+    let accounts = AccountStore::open(accounts_path)?;
+    let tokens = match std::env::var(SESSION_SECRET_ENV) {
+        Ok(secret) => SessionTokens::new(secret.as_bytes()),
+        Err(_) => {
+            tracing::warn!("{SESSION_SECRET_ENV} not set; sessions will not survive a restart");
+            SessionTokens::ephemeral()
+        }
+    };
+
+    let metrics = Metrics::default();
+    let (flush_writers, flush) = Flush::new();
+    let (history_tx, history_writer) = histories.start_writer(flush.clone());
+    let (log_tx, log_cuts, log_writer) = game_logs.start_writer(flush.clone());
+    let (snapshot_tx, snapshot_writer) = game_logs.start_snapshot_writer(flush.clone(), log_cuts);
+    let (ledger_tx, ledger_writer) = accounts.start_ledger_writer(flush);
+    let recorder = TableRecorder {
+        histories: history_tx,
+        log: log_tx,
+        snapshots: snapshot_tx,
+        ledger: ledger_tx,
+        metrics: metrics.clone(),
+    };
+
+    let tables = TableRegistry::new(recorder.clone());
+    let policy = recovery_policy()?;
+    for table_id in game_logs.table_ids().await? {
+        let log = game_logs.read(&table_id).await?;
+        let Some(LoggedCommand::Opened { config, managed, .. }) = log.first().map(|e| &e.command) else {
+            continue;
+        };
+        // The director keeps entrants, seats and the clock in memory only, so a multi-table
+        // tournament does not survive a restart: its tables are left behind and it starts over.
+        if managed.is_some() {
+            tracing::warn!(%table_id, "not recovering a multi-table tournament table");
+            continue;
+        }
+        if matches!(log.last().map(|e| &e.command), Some(LoggedCommand::Close)) {
+            continue;
+        }
+        let config = config.as_ref().clone();
+        let snapshot = game_logs.read_snapshot(&table_id).await?;
+        let recovered = GameManager::recover(snapshot, &log, policy, recorder.clone()).map_err(anyhow::Error::msg)?;
+        tables.insert(table_id, config, recovered);
+    }
+    if tables.get(MAIN_TABLE_ID).is_none() {
+        let refunded = accounts.refund_table(MAIN_TABLE_ID).await.map_err(anyhow::Error::msg)?;
+        if refunded > 0 {
+            tracing::warn!(refunded, "refunded buy-ins left on a table with no game log");
+        }
+        let config = load_table_config()?;
+        let manager = match std::env::var(TABLE_SEED_ENV) {
+            Ok(seed) => {
+                tracing::warn!("{TABLE_SEED_ENV} is set; the main table deals the same cards every run");
+                GameManager::seeded(config.clone(), seed.parse()?)
+            }
+            Err(_) => GameManager::new(config.clone()),
+        };
+        let started = manager.launch(MAIN_TABLE_ID.into(), recorder.clone());
+        tables.insert(MAIN_TABLE_ID.into(), config, started);
+    }
+
+    start_reconciler(tables.clone(), accounts.clone());
+
+    let director = match std::env::var(TOURNAMENT_CONFIG_ENV) {
+        Ok(path) => {
+            let mut config: MultiTableConfig = serde_json::from_str(&std::fs::read_to_string(&path)?)?;
+            config.tournament.load_schedule_file()?;
+            Some(TournamentDirector::start(config, recorder, accounts.clone()).map_err(anyhow::Error::msg)?)
+        }
+        Err(_) => None,
+    };
+
+    let (start_drain, draining) = watch::channel(false);
+    let drain = drain_on_signal(tables.clone(), director.as_ref().map(|(tx, _)| tx.clone()), start_drain);
+    let admins = Arc::new(admin_accounts(&accounts).await?);
+    let state = app::AppState { tables, histories, accounts, tokens, metrics, draining, admins };
+    let app = app::create_routes(state, director);
 
     let addr: SocketAddr = format!("{SERVER_ADDRESS}:{SERVER_PORT}").parse()?;
     
@@ -28,14 +156,106 @@ async fn main() -> Result<()> {
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
     axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
+        .with_graceful_shutdown(drain)
         .await?;
 
+    let _ = flush_writers.send(true);
+    for writer in [history_writer, log_writer, snapshot_writer, ledger_writer] {
+        writer.await?;
+    }
     tracing::info!("server stopped");
     Ok(())
 }
 
+fn load_table_config() -> Result<TableConfig> {
+    if let Ok(path) = std::env::var(TABLE_CONFIG_ENV) {
+        let raw = std::fs::read_to_string(&path)?;
+        let mut config: TableConfig = serde_json::from_str(&raw)?;
+        if let Some(tournament) = &mut config.sit_and_go {
+            tournament.load_schedule_file()?;
+        }
+        return Ok(config);
+    }
+
+    let variant = match std::env::var(GAME_VARIANT_ENV) {
+        Ok(name) => serde_json::from_value::<GameVariant>(serde_json::Value::String(name))?,
+        Err(_) => GameVariant::default(),
+    };
+
+    Ok(TableConfig::single(variant))
+}
+
+fn recovery_policy() -> Result<RecoveryPolicy> {
+    match std::env::var(RECOVERY_POLICY_ENV) {
+        Ok(name) => Ok(serde_json::from_value(serde_json::Value::String(name))?),
+        Err(_) => Ok(RecoveryPolicy::default()),
+    }
+}
+
+/// Account ids from a comma-separated list; nobody can use the admin endpoints without one.
+async fn admin_accounts(accounts: &AccountStore) -> Result<HashSet<String>> {
+    let admins: HashSet<String> = std::env::var(ADMIN_ACCOUNTS_ENV)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+        .collect();
+    if admins.is_empty() {
+        tracing::warn!("{ADMIN_ACCOUNTS_ENV} not set; the admin endpoints are closed");
+    }
+    for id in &admins {
+        if accounts.account(id).await.map_err(anyhow::Error::msg)?.is_none() {
+            tracing::warn!(%id, "{ADMIN_ACCOUNTS_ENV} entry is not a known account id; operators are listed by id, not username");
+        }
+    }
+    Ok(admins)
+}
+
 async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        let mut terminate = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+            .expect("SIGTERM handler installs");
+        tokio::select! {
+            _ = tokio::signal::ctrl_c() => {}
+            _ = terminate.recv() => {}
+        }
+    }
+    #[cfg(not(unix))]
     tokio::signal::ctrl_c().await.ok();
     tracing::info!("received shutdown signal");
 }
+
+/// On SIGTERM or Ctrl-C, stops taking players and waits, up to a limit, for every table to finish
+/// its hand and save itself. Tables still mid-hand after that are left to recovery on restart.
+async fn drain_on_signal(
+    tables: TableRegistry,
+    director: Option<mpsc::Sender<DirectorCommand>>,
+    start_drain: watch::Sender<bool>,
+) {
+    shutdown_signal().await;
+    let _ = start_drain.send(true);
+
+    let mut drained = vec![];
+    for (table_id, commands) in tables.command_senders() {
+        let (tx, rx) = oneshot::channel();
+        if commands.send(GameCommand::Drain { reply: tx }).await.is_ok() {
+            drained.push((table_id, rx));
+        }
+    }
+    if let Some(director) = director {
+        let (tx, rx) = oneshot::channel();
+        if director.send(DirectorCommand::Drain { reply: tx }).await.is_ok() {
+            drained.push(("tournament".into(), rx));
+        }
+    }
+
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(DRAIN_TIMEOUT_SECS);
+    for (table_id, rx) in drained {
+        if tokio::time::timeout_at(deadline, rx).await.is_err() {
+            tracing::warn!(%table_id, "table did not drain in time; its hand will be recovered on restart");
+        }
+    }
+    tracing::info!("drained; shutting down");
+}
@@ -0,0 +1,11 @@
+pub const STARTING_BANKROLL: u64 = 10_000;
+
+pub const DEFAULT_BUY_IN: u64 = 1000;
+
+pub const MIN_USERNAME_LEN: usize = 3;
+
+pub const MAX_USERNAME_LEN: usize = 32;
+
+pub const MIN_PASSWORD_LEN: usize = 8;
+
+pub const SESSION_TOKEN_TTL_SECS: i64 = 24 * 60 * 60;
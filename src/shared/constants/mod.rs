@@ -1,3 +1,5 @@
 pub mod server;
+pub mod accounts;
 
-pub use server::*;
\ No newline at end of file
+pub use server::*;
+pub use accounts::*;
@@ -2,4 +2,52 @@ pub const SERVER_ADDRESS: &str = "0.0.0.0";
 
 pub const SERVER_PORT: u16 = 8080;
 
-pub const INITIAL_HAND_SIZE: usize = 2;
\ No newline at end of file
+pub const GAME_VARIANT_ENV: &str = "GAME_VARIANT";
+
+pub const TABLE_CONFIG_ENV: &str = "TABLE_CONFIG";
+
+pub const TABLE_SEED_ENV: &str = "TABLE_SEED";
+
+pub const TOURNAMENT_CONFIG_ENV: &str = "TOURNAMENT_CONFIG";
+
+pub const HAND_HISTORY_PATH_ENV: &str = "HAND_HISTORY_PATH";
+
+pub const DEFAULT_HAND_HISTORY_PATH: &str = "hand_histories.jsonl";
+
+pub const MAIN_TABLE_ID: &str = "main";
+
+pub const EXPORT_OHH_COMMAND: &str = "export-ohh";
+
+pub const GAME_LOG_DIR_ENV: &str = "GAME_LOG_DIR";
+
+pub const DEFAULT_GAME_LOG_DIR: &str = "game_logs";
+
+pub const REPLAY_LOG_COMMAND: &str = "replay-log";
+
+pub const RECOVERY_POLICY_ENV: &str = "RECOVERY_POLICY";
+
+pub const ACCOUNTS_DB_PATH_ENV: &str = "ACCOUNTS_DB_PATH";
+
+pub const DEFAULT_ACCOUNTS_DB_PATH: &str = "accounts.db";
+
+pub const RECONCILE_INTERVAL_SECS: u64 = 300;
+
+pub const SESSION_SECRET_ENV: &str = "SESSION_SECRET";
+
+pub const DEFAULT_LEADERBOARD_SIZE: usize = 20;
+
+pub const MAX_LEADERBOARD_SIZE: usize = 100;
+
+pub const API_DOCS_COMMAND: &str = "api-docs";
+
+pub const VERIFY_SHUFFLE_COMMAND: &str = "verify-shuffle";
+
+pub const DEFAULT_API_DOCS_DIR: &str = "docs";
+
+pub const HAND_DURATION_BUCKETS_SECS: &[f64] = &[5.0, 10.0, 20.0, 30.0, 45.0, 60.0, 90.0, 120.0, 180.0, 300.0, 600.0];
+
+pub const DRAIN_TIMEOUT_SECS: u64 = 120;
+
+pub const ADMIN_ACCOUNTS_ENV: &str = "ADMIN_ACCOUNTS";
+
+pub const MAX_ANNOUNCEMENT_LEN: usize = 500;